@@ -0,0 +1,572 @@
+use crate::builtins::Elementary;
+use crate::decimal::{context, digits, exact_root, integer_power, root, round, set_context, Context, Rounding, GUARD_DIGITS};
+use crate::number::{Number, NumberError};
+use crate::parse::Literal;
+use crate::transcendental;
+use num::bigint::Sign;
+use num::{BigInt, One, Signed, ToPrimitive, Zero};
+use std::cmp::Ordering;
+use std::f64::consts::LOG10_E;
+use std::fmt::{Display, Formatter};
+
+/// A decimal `value * 10^-scale` of arbitrary size, limited by the [`Context`]
+/// instead of a fixed width.
+///
+/// Trailing zeros are always removed, so equal numbers have equal representations.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BigDecimal {
+    value: BigInt,
+    scale: i64,
+}
+
+impl BigDecimal {
+    pub fn new(value: BigInt, scale: i64) -> Self {
+        let mut decimal = Self { value, scale };
+        decimal.pack();
+        decimal
+    }
+
+    fn pack(&mut self) {
+        if self.value.is_zero() {
+            self.scale = 0;
+            return;
+        }
+        let digits = self.value.magnitude().to_string();
+        let zeros = digits.len() - digits.trim_end_matches('0').len();
+        if zeros > 0 {
+            self.value /= ten(zeros as i64);
+            self.scale -= zeros as i64;
+        }
+    }
+
+    /// The number of digits needed to write the value without an exponent.
+    fn width(&self) -> u64 {
+        let digits = digits(&self.value) as i64;
+        (digits.max(self.scale) + (-self.scale).max(0)) as u64
+    }
+
+    /// Fails with [`NumberError::Overflow`] if writing the value needs more digits than the context allows.
+    fn limit(self) -> Result<Self, NumberError> {
+        if self.width() > context().max_digits {
+            return Err(NumberError::Overflow);
+        }
+        Ok(self)
+    }
+
+    /// Both coefficients at the larger of the two scales, together with that scale.
+    fn align(&self, other: &Self) -> (BigInt, BigInt, i64) {
+        let scale = self.scale.max(other.scale);
+        (&self.value * ten(scale - self.scale), &other.value * ten(scale - other.scale), scale)
+    }
+
+    fn compare(&self, other: &Self) -> Ordering {
+        let (a, b, _) = self.align(other);
+        a.cmp(&b)
+    }
+
+    /// Rounds `numerator / denominator * 10^exponent` to the context's precision.
+    fn quotient(numerator: &BigInt, denominator: &BigInt, exponent: i64) -> Result<Self, NumberError> {
+        if denominator.is_zero() {
+            return Err(NumberError::DivisionByZero);
+        }
+        if numerator.is_zero() {
+            return Ok(BigDecimal::new(BigInt::zero(), 0));
+        }
+        let context = context();
+        let (coefficient, exponent) = round(numerator, denominator, exponent, context.precision.max(1) as i64, None, context.rounding);
+        BigDecimal::new(coefficient, -exponent).limit()
+    }
+
+    /// Rounds to an integer in the given direction.
+    fn integer(&self, rounding: Rounding) -> Self {
+        match self.scale {
+            ..=0 => self.clone(),
+            scale => BigDecimal::new(rounding.divide(&self.value, &ten(scale)), 0),
+        }
+    }
+
+    /// The power of ten just above a non-zero value, `10^(m-1) <= |x| < 10^m`.
+    fn magnitude(&self) -> i64 {
+        digits(&self.value) as i64 - self.scale
+    }
+
+    /// The common logarithm of the absolute value, estimated from the leading digits.
+    fn order(&self) -> f64 {
+        let shift = digits(&self.value).saturating_sub(f64::DIGITS as usize) as i64;
+        (&self.value / ten(shift)).to_f64().unwrap().abs().log10() + (shift - self.scale) as f64
+    }
+
+    /// The value as a fixed point number with `scale` decimals, truncated.
+    fn fixed(&self, scale: u32) -> BigInt {
+        match scale as i64 - self.scale {
+            shift @ 0.. => &self.value * ten(shift),
+            shift => &self.value / ten(-shift),
+        }
+    }
+
+    fn positive(self, operation: &str) -> Result<Self, NumberError> {
+        if !self.value.is_positive() {
+            return Err(NumberError::Domain(format!("{operation} of non-positive number {self}")));
+        }
+        Ok(self)
+    }
+
+    fn is_one(&self) -> bool {
+        self.value.is_one() && self.scale == 0
+    }
+
+    /// Rounds a [`transcendental`] function of a result below `10^magnitude` to the context's precision, see
+    /// [`transcendental::correctly_rounded`].
+    fn correctly_rounded(magnitude: i64, approximate: impl Fn(u32) -> BigInt) -> Result<Self, NumberError> {
+        let digits = (context().precision as i64 - magnitude).clamp(0, u32::MAX.into()) as u32;
+        transcendental::correctly_rounded(digits, approximate, |approximation, scale| BigDecimal::quotient(approximation, &BigInt::one(), -(scale as i64)))
+    }
+
+    /// Evaluates `f` with the guard digits and `extra` more of precision, then rounds its result to the context,
+    /// for functions composed of correctly rounded ones.
+    fn composed(extra: u32, f: impl FnOnce() -> Result<Self, NumberError>) -> Result<Self, NumberError> {
+        let context = context();
+        set_context(Context {
+            precision: context.precision + GUARD_DIGITS as u32 + extra,
+            rounding: Rounding::HalfEven,
+            ..context
+        });
+        let result = f();
+        set_context(context);
+        let result = result?;
+        BigDecimal::quotient(&result.value, &BigInt::one(), -result.scale)
+    }
+
+    /// `e^x` at the scale, the argument gets as many more digits as the result has integer digits, as far as it
+    /// has them.
+    fn exponential(&self, magnitude: i64, scale: u32) -> BigInt {
+        let working = (scale as i64 + magnitude).min(self.scale).max(scale.into()) as u32;
+        transcendental::exp(&self.fixed(working), working) / ten(working as i64 - scale as i64)
+    }
+
+    /// `ln(x)` of a positive value at the scale, as `ln(m) + k·ln(10)` of `x = m · 10^k` for values far from one,
+    /// which keeps every digit of tiny and huge values.
+    fn logarithm(&self, scale: u32) -> BigInt {
+        let k = match self.magnitude() {
+            0..=1 => 0,
+            magnitude => magnitude,
+        };
+        // the mantissa is at least 0.1, its truncation is multiplied by at most 10, and ln(10) by k
+        let working = scale + digits(&k.into()) as u32 + 2;
+        let mantissa = BigDecimal { value: self.value.clone(), scale: self.scale + k };
+        let logarithm = transcendental::ln(&mantissa.fixed(working), working) + k * transcendental::ln(&ten(working as i64 + 1), working);
+        logarithm / ten(working as i64 - scale as i64)
+    }
+
+    /// The magnitude of `ln(x)` for a positive value other than one, an upper bound for values far from one.
+    fn logarithm_magnitude(&self) -> i64 {
+        match self.magnitude() {
+            0..=1 => BigDecimal::new(&self.value - ten(self.scale), self.scale).magnitude(),
+            magnitude => digits(&magnitude.into()) as i64 + 1,
+        }
+    }
+
+    /// The root is truncated to a few digits more than the context's precision, see [`root`].
+    fn root(self, degree: u32) -> Result<Self, NumberError> {
+        if self.value.is_zero() {
+            return Ok(self);
+        }
+        let exponent = self.magnitude().div_euclid(degree.into()) - context().precision as i64 - 2;
+        BigDecimal::quotient(&root(&self.value, self.scale, degree, exponent), &BigInt::from(2), exponent)
+    }
+
+    /// Raises to an integer power by squaring, exact as long as the result has at most the context's precision.
+    fn powi(self, exponent: BigInt) -> Result<Self, NumberError> {
+        if self.value.is_zero() {
+            return match BigInt::sign(&exponent) {
+                Sign::Minus => Err(NumberError::DivisionByZero),
+                Sign::NoSign => Ok(BigDecimal::new(BigInt::one(), 0)),
+                Sign::Plus => Ok(self),
+            };
+        }
+        let context = context();
+        let working = context.precision as usize + GUARD_DIGITS;
+        match integer_power(&self.value, -self.scale, &exponent, working, context.max_digits as i64) {
+            Ok((numerator, denominator, exponent)) => BigDecimal::quotient(&numerator, &denominator, exponent),
+            // tiny values need as many digits to be written as huge ones
+            Err(_) => Err(NumberError::Overflow),
+        }
+    }
+
+    /// Integer exponents are exact, see [`BigDecimal::powi`], fractional exponents are correctly rounded to the
+    /// context's precision, see [`BigDecimal::fractional_power`].
+    fn power(self, exponent: Self) -> Result<Self, NumberError> {
+        if exponent.scale <= 0 {
+            return self.powi(exponent.value * ten(-exponent.scale));
+        }
+        if self.value.is_negative() {
+            return Err(NumberError::Domain(format!("{self} cannot be raised to the fractional power {exponent}")));
+        }
+        if self.value.is_zero() {
+            return self.powi(exponent.value.signum());
+        }
+        if let Some((numerator, denominator, power)) = exact_root(&self.value, self.scale, &exponent.value, exponent.scale) {
+            // the denominator divides a power of ten with less than four times its digits, so the root is exact
+            let precision = (digits(&numerator) + 4 * digits(&denominator)) as i64;
+            let (coefficient, root) = round(&numerator, &denominator, 0, precision, None, Rounding::Down);
+            return BigDecimal::new(coefficient, -root).powi(power);
+        }
+        self.fractional_power(exponent)
+    }
+
+    /// `e^(y·ln(x))` of a positive `x`, the logarithm gets as many more digits as the exponent has integer digits,
+    /// so its error stays below a unit of the product.
+    fn fractional_power(self, exponent: Self) -> Result<Self, NumberError> {
+        let estimate = exponent.to_f64() * self.order();
+        if estimate.is_nan() || estimate.abs() > context().max_digits as f64 {
+            return Err(NumberError::Overflow);
+        }
+        let magnitude = estimate.floor() as i64 + 1;
+        let extra = exponent.magnitude().max(0) as u32 + 1;
+        BigDecimal::correctly_rounded(magnitude.min(0), |scale| {
+            let working = scale + magnitude.max(0) as u32;
+            let logarithm = self.logarithm(working + extra);
+            let product = logarithm * &exponent.value / ten(exponent.scale + extra as i64);
+            transcendental::exp(&product, working) / ten(working as i64 - scale as i64)
+        })
+    }
+
+    fn absolute(&self) -> Self {
+        BigDecimal::new(Signed::abs(&self.value), self.scale)
+    }
+
+    /// Combines `e^x` and `e^-x` of a positive `x` and halves the result, `e^-x` is left out once it is below
+    /// the precision.
+    fn hyperbolic(self, f: impl FnOnce(Self, Self) -> Result<Self, NumberError>) -> Result<Self, NumberError> {
+        let power = self.exp()?;
+        let inverse = match power.magnitude() > context().precision as i64 {
+            true => BigDecimal::new(BigInt::zero(), 0),
+            false => one().try_div(power.clone())?,
+        };
+        f(power, inverse)?.try_div(BigDecimal::new(2.into(), 0))
+    }
+
+    /// `ln(x + √r)` of a positive `x` and a radicand close to `x²`, which is `ln(2x)` up to far below the
+    /// precision for huge values.
+    fn inverse_hyperbolic(self, radicand: impl FnOnce() -> Result<Self, NumberError>) -> Result<Self, NumberError> {
+        if self.magnitude() > context().precision as i64 {
+            return self.try_mul(BigDecimal::new(2.into(), 0))?.ln();
+        }
+        self.try_add(radicand()?.sqrt()?)?.ln()
+    }
+
+    fn to_f64(&self) -> f64 {
+        format!("{}e{}", self.value, -self.scale).parse().unwrap()
+    }
+}
+
+fn ten(power: i64) -> BigInt {
+    BigInt::from(10).pow(power as u32)
+}
+
+fn one() -> BigDecimal {
+    BigDecimal::new(BigInt::one(), 0)
+}
+
+impl Display for BigDecimal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.value.is_negative() { "-" } else { "" };
+        let digits = self.value.magnitude().to_string();
+        match usize::try_from(self.scale) {
+            Err(_) => write!(f, "{sign}{digits}{}", "0".repeat(self.scale.unsigned_abs() as usize)),
+            Ok(0) => write!(f, "{sign}{digits}"),
+            Ok(scale) if digits.len() > scale => {
+                let (integer, fraction) = digits.split_at(digits.len() - scale);
+                write!(f, "{sign}{integer}.{fraction}")
+            }
+            Ok(scale) => write!(f, "{sign}0.{}{digits}", "0".repeat(scale - digits.len())),
+        }
+    }
+}
+
+impl Number for BigDecimal {
+    fn from_literal(literal: &Literal) -> Option<Self> {
+        if literal.radix != 10 {
+            return BigInt::parse_bytes(literal.integer.as_bytes(), literal.radix).map(|value| BigDecimal::new(value, 0));
+        }
+        let (digits, exponent) = literal.to_digits();
+        BigDecimal::new(digits.parse().ok()?, -exponent).limit().ok()
+    }
+
+    fn try_add(self, rhs: Self) -> Result<Self, NumberError> {
+        let (a, b, scale) = self.align(&rhs);
+        BigDecimal::new(a + b, scale).limit()
+    }
+
+    fn try_sub(self, rhs: Self) -> Result<Self, NumberError> {
+        let (a, b, scale) = self.align(&rhs);
+        BigDecimal::new(a - b, scale).limit()
+    }
+
+    fn try_mul(self, rhs: Self) -> Result<Self, NumberError> {
+        BigDecimal::quotient(&(self.value * rhs.value), &BigInt::one(), -(self.scale + rhs.scale))
+    }
+
+    fn try_div(self, rhs: Self) -> Result<Self, NumberError> {
+        BigDecimal::quotient(&self.value, &rhs.value, rhs.scale - self.scale)
+    }
+
+    fn try_rem(self, rhs: Self) -> Result<Self, NumberError> {
+        if rhs.value.is_zero() {
+            return Err(NumberError::DivisionByZero);
+        }
+        let (a, b, scale) = self.align(&rhs);
+        Ok(BigDecimal::new(a % b, scale))
+    }
+
+    fn try_pow(self, rhs: Self) -> Result<Self, NumberError> {
+        self.power(rhs)
+    }
+
+    fn try_neg(self) -> Result<Self, NumberError> {
+        Ok(BigDecimal::new(-self.value, self.scale))
+    }
+
+    fn to_float(&self) -> Option<f64> {
+        Some(self.to_f64())
+    }
+
+    fn from_bool(value: bool) -> Self {
+        BigDecimal::new(u8::from(value).into(), 0)
+    }
+
+    fn try_cmp(&self, other: &Self) -> Result<Option<Ordering>, NumberError> {
+        Ok(Some(self.compare(other)))
+    }
+}
+
+/// Rounding and sign functions are exact. Roots, `exp`, `ln`, `sin`, `cos`, `atan` and the constants are
+/// correctly rounded to the [`Context`], the other transcendental functions are composed of them with more
+/// digits and rounded once.
+impl Elementary for BigDecimal {
+    fn sqrt(self) -> Result<Self, NumberError> {
+        if self.value.is_negative() {
+            return Err(NumberError::Domain(format!("square root of negative number {self}")));
+        }
+        self.root(2)
+    }
+
+    fn cbrt(self) -> Result<Self, NumberError> {
+        self.root(3)
+    }
+
+    fn exp(self) -> Result<Self, NumberError> {
+        if self.value.is_zero() {
+            return Ok(one());
+        }
+        // e^x has about x·log10(e) digits before or after the point, like powers it overflows beyond the context
+        let estimate = self.to_f64() * LOG10_E;
+        if estimate.abs() > context().max_digits as f64 {
+            return Err(NumberError::Overflow);
+        }
+        let magnitude = estimate.floor() as i64 + 1;
+        BigDecimal::correctly_rounded(magnitude.min(0), |scale| self.exponential(magnitude, scale))
+    }
+
+    fn ln(self) -> Result<Self, NumberError> {
+        let x = self.positive("logarithm")?;
+        if x.is_one() {
+            return Ok(BigDecimal::new(BigInt::zero(), 0));
+        }
+        BigDecimal::correctly_rounded(x.logarithm_magnitude(), |scale| x.logarithm(scale))
+    }
+
+    fn log10(self) -> Result<Self, NumberError> {
+        let x = self.positive("logarithm")?;
+        if x.value.is_one() {
+            return Ok(BigDecimal::new((-x.scale).into(), 0));
+        }
+        BigDecimal::composed(0, || x.ln()?.try_div(BigDecimal::new(10.into(), 0).ln()?))
+    }
+
+    fn log2(self) -> Result<Self, NumberError> {
+        let x = self.positive("logarithm")?;
+        let bits = x.value.bits() as i64;
+        if x.scale == 0 && x.value == BigInt::one() << (bits - 1) {
+            return Ok(BigDecimal::new((bits - 1).into(), 0));
+        }
+        if x.scale > 0 && x.value == BigInt::from(5).pow(x.scale as u32) {
+            return Ok(BigDecimal::new((-x.scale).into(), 0));
+        }
+        BigDecimal::composed(0, || x.ln()?.try_div(BigDecimal::new(2.into(), 0).ln()?))
+    }
+
+    fn log(self, base: Self) -> Result<Self, NumberError> {
+        let x = self.positive("logarithm")?;
+        BigDecimal::composed(0, || x.ln()?.try_div(base.ln()?))
+    }
+
+    fn sin(self) -> Result<Self, NumberError> {
+        if self.value.is_zero() {
+            return Ok(self);
+        }
+        BigDecimal::correctly_rounded(self.magnitude().min(1), |scale| transcendental::sine(&self.fixed(scale), scale, false))
+    }
+
+    fn cos(self) -> Result<Self, NumberError> {
+        if self.value.is_zero() {
+            return Ok(one());
+        }
+        BigDecimal::correctly_rounded(1, |scale| transcendental::sine(&self.fixed(scale), scale, true))
+    }
+
+    fn tan(self) -> Result<Self, NumberError> {
+        if self.value.is_zero() {
+            return Ok(self);
+        }
+        BigDecimal::composed(0, || self.clone().sin()?.try_div(self.cos()?))
+    }
+
+    /// `asin(x) = atan(x / √((1 - x)(1 + x)))`, where both factors are exact.
+    fn asin(self) -> Result<Self, NumberError> {
+        if self.value.is_zero() {
+            return Ok(self);
+        }
+        match self.absolute().compare(&one()) {
+            Ordering::Greater => Err(NumberError::Domain(format!("arcsine of {self} outside of [-1, 1]"))),
+            Ordering::Equal => BigDecimal::composed(0, || BigDecimal::pi()?.try_div(BigDecimal::new(BigInt::from(2) * self.value.signum(), 0))),
+            Ordering::Less => BigDecimal::composed(0, || {
+                let (below, above) = (one().try_sub(self.clone())?, one().try_add(self.clone())?);
+                self.try_div(below.try_mul(above)?.sqrt()?)?.atan()
+            }),
+        }
+    }
+
+    /// `acos(x) = 2·atan(√((1 - x) / (1 + x)))`, which has no cancellation near one.
+    fn acos(self) -> Result<Self, NumberError> {
+        if self.is_one() {
+            return Ok(BigDecimal::new(BigInt::zero(), 0));
+        }
+        match self.absolute().compare(&one()) {
+            Ordering::Greater => Err(NumberError::Domain(format!("arccosine of {self} outside of [-1, 1]"))),
+            Ordering::Equal => BigDecimal::pi(),
+            Ordering::Less => BigDecimal::composed(0, || {
+                let (below, above) = (one().try_sub(self.clone())?, one().try_add(self)?);
+                below.try_div(above)?.sqrt()?.atan()?.try_mul(BigDecimal::new(2.into(), 0))
+            }),
+        }
+    }
+
+    fn atan(self) -> Result<Self, NumberError> {
+        if self.value.is_zero() {
+            return Ok(self);
+        }
+        BigDecimal::correctly_rounded(self.magnitude().min(1), |scale| transcendental::atan(&self.fixed(scale), scale))
+    }
+
+    /// `(e^x - e^-x) / 2`, small arguments get as many more digits as the difference cancels.
+    fn sinh(self) -> Result<Self, NumberError> {
+        if self.value.is_zero() {
+            return Ok(self);
+        }
+        let extra = (-self.magnitude()).max(0) as u32;
+        let sign = BigDecimal::new(self.value.signum(), 0);
+        BigDecimal::composed(extra, || self.absolute().hyperbolic(|power, inverse| power.try_sub(inverse))?.try_mul(sign))
+    }
+
+    fn cosh(self) -> Result<Self, NumberError> {
+        if self.value.is_zero() {
+            return Ok(one());
+        }
+        BigDecimal::composed(0, || self.absolute().hyperbolic(|power, inverse| power.try_add(inverse)))
+    }
+
+    /// Beyond the precision `tanh(x)` is closer to `±1` than any other value, otherwise it is `sinh(x) / cosh(x)`.
+    fn tanh(self) -> Result<Self, NumberError> {
+        if self.value.is_zero() {
+            return Ok(self);
+        }
+        let precision = context().precision as i64 + 2 * GUARD_DIGITS as i64;
+        if self.to_f64().abs() * LOG10_E > precision as f64 {
+            // any value strictly between 1 - 10^-precision and 1 rounds like tanh(x)
+            return BigDecimal::quotient(&((ten(precision) - 1) * self.value.signum()), &BigInt::one(), -precision);
+        }
+        BigDecimal::composed(0, || self.clone().sinh()?.try_div(self.cosh()?))
+    }
+
+    /// `ln(x + √(x² + 1))` with the sign of `x`, small arguments get as many more digits as the logarithm cancels.
+    fn asinh(self) -> Result<Self, NumberError> {
+        if self.value.is_zero() {
+            return Ok(self);
+        }
+        let extra = (-self.magnitude()).max(0) as u32;
+        let sign = BigDecimal::new(self.value.signum(), 0);
+        let x = self.absolute();
+        BigDecimal::composed(extra, || x.clone().inverse_hyperbolic(move || x.clone().try_mul(x)?.try_add(one()))?.try_mul(sign))
+    }
+
+    /// `ln(x + √(x² - 1))`, arguments close to one get as many more digits as the logarithm cancels.
+    fn acosh(self) -> Result<Self, NumberError> {
+        match self.compare(&one()) {
+            Ordering::Less => Err(NumberError::Domain(format!("inverse hyperbolic cosine of {self} below 1"))),
+            Ordering::Equal => Ok(BigDecimal::new(BigInt::zero(), 0)),
+            Ordering::Greater => {
+                let extra = (-self.clone().try_sub(one())?.magnitude()).max(0) as u32;
+                BigDecimal::composed(extra, || {
+                    let (below, above) = (self.clone().try_sub(one())?, self.clone().try_add(one())?);
+                    self.inverse_hyperbolic(|| below.try_mul(above))
+                })
+            }
+        }
+    }
+
+    /// `ln((1 + x) / (1 - x)) / 2`, small arguments get as many more digits as the logarithm cancels.
+    fn atanh(self) -> Result<Self, NumberError> {
+        if self.value.is_zero() {
+            return Ok(self);
+        }
+        if self.absolute().compare(&one()) != Ordering::Less {
+            return Err(NumberError::Domain(format!("inverse hyperbolic tangent of {self} outside of (-1, 1)")));
+        }
+        let extra = (-self.magnitude()).max(0) as u32;
+        BigDecimal::composed(extra, || {
+            let (above, below) = (one().try_add(self.clone())?, one().try_sub(self)?);
+            above.try_div(below)?.ln()?.try_div(BigDecimal::new(2.into(), 0))
+        })
+    }
+
+    fn pi() -> Result<Self, NumberError> {
+        BigDecimal::correctly_rounded(1, transcendental::pi)
+    }
+
+    fn e() -> Result<Self, NumberError> {
+        BigDecimal::correctly_rounded(1, |scale| transcendental::exp(&ten(scale.into()), scale))
+    }
+
+    fn abs(self) -> Result<Self, NumberError> {
+        Ok(self.absolute())
+    }
+
+    fn sign(self) -> Result<Self, NumberError> {
+        Ok(BigDecimal::new(self.value.signum(), 0))
+    }
+
+    fn minimum(self, other: Self) -> Result<Self, NumberError> {
+        Ok(if self.compare(&other) == Ordering::Greater { other } else { self })
+    }
+
+    fn maximum(self, other: Self) -> Result<Self, NumberError> {
+        Ok(if self.compare(&other) == Ordering::Less { other } else { self })
+    }
+
+    fn floor(self) -> Result<Self, NumberError> {
+        Ok(self.integer(Rounding::Floor))
+    }
+
+    fn ceil(self) -> Result<Self, NumberError> {
+        Ok(self.integer(Rounding::Ceiling))
+    }
+
+    fn round(self) -> Result<Self, NumberError> {
+        Ok(self.integer(Rounding::HalfUp))
+    }
+
+    fn trunc(self) -> Result<Self, NumberError> {
+        Ok(self.integer(Rounding::Down))
+    }
+}
@@ -0,0 +1,155 @@
+use crate::builtins::Elementary;
+use crate::decimal::{context, digits};
+use crate::number::{Number, NumberError};
+use crate::parse::Literal;
+use num::integer::Roots;
+use num::{BigInt, Integer, One, Signed, ToPrimitive, Zero};
+use std::cmp::Ordering;
+use std::f64::consts::LOG10_2;
+
+/// The decimal logarithm of the magnitude of a non-zero value.
+fn log10(value: &BigInt) -> f64 {
+    let shift = value.bits().saturating_sub(64);
+    (value.magnitude() >> shift).to_f64().unwrap_or(f64::INFINITY).log10() + shift as f64 * LOG10_2
+}
+
+/// Fails with [`NumberError::Overflow`] if the value has more digits than the [`Context`](crate::decimal::Context) allows.
+fn limit(value: BigInt) -> Result<BigInt, NumberError> {
+    let max_digits = context().max_digits;
+    if (value.bits() as f64 * LOG10_2).ceil() <= max_digits as f64 || digits(&value) as u64 <= max_digits {
+        return Ok(value);
+    }
+    Err(NumberError::Overflow)
+}
+
+/// Integers of arbitrary size, `/` truncates towards zero like for the fixed width integers.
+///
+/// Results with more digits than [`Context::max_digits`](crate::decimal::Context::max_digits) fail with
+/// [`NumberError::Overflow`], powers estimate their size first, so `9^9^9` returns immediately.
+impl Number for BigInt {
+    fn from_literal(literal: &Literal) -> Option<Self> {
+        if literal.radix != 10 {
+            return BigInt::parse_bytes(literal.integer.as_bytes(), literal.radix);
+        }
+        let (digits, exponent) = literal.to_digits();
+        let digits = digits.trim_start_matches('0');
+        if digits.is_empty() {
+            return Some(BigInt::zero());
+        }
+        let width = digits.len() as i64 + exponent;
+        if width <= 0 || width as u64 > context().max_digits {
+            return None;
+        }
+        let integer = match exponent {
+            0.. => format!("{digits}{}", "0".repeat(exponent as usize)),
+            _ => digits[width as usize..].chars().all(|c| c == '0').then(|| digits[..width as usize].to_string())?,
+        };
+        integer.parse().ok()
+    }
+
+    fn try_add(self, rhs: Self) -> Result<Self, NumberError> {
+        limit(self + rhs)
+    }
+
+    fn try_sub(self, rhs: Self) -> Result<Self, NumberError> {
+        limit(self - rhs)
+    }
+
+    fn try_mul(self, rhs: Self) -> Result<Self, NumberError> {
+        limit(self * rhs)
+    }
+
+    fn try_div(self, rhs: Self) -> Result<Self, NumberError> {
+        if rhs.is_zero() {
+            return Err(NumberError::DivisionByZero);
+        }
+        Ok(self / rhs)
+    }
+
+    fn try_rem(self, rhs: Self) -> Result<Self, NumberError> {
+        if rhs.is_zero() {
+            return Err(NumberError::DivisionByZero);
+        }
+        Ok(self % rhs)
+    }
+
+    fn try_pow(self, rhs: Self) -> Result<Self, NumberError> {
+        if rhs.is_negative() {
+            return Err(NumberError::Domain(format!("{self} cannot be raised to the negative power {rhs}, the result would not be an integer")));
+        }
+        if self.is_zero() || Signed::abs(&self).is_one() {
+            return Ok(match rhs.is_zero() {
+                true => BigInt::one(),
+                false if self.is_negative() && rhs.is_even() => BigInt::one(),
+                false => self,
+            });
+        }
+        let exponent = rhs
+            .to_u32()
+            .filter(|&exponent| exponent as f64 * log10(&self) < context().max_digits as f64 + 1.0)
+            .ok_or(NumberError::Overflow)?;
+        limit(self.pow(exponent))
+    }
+
+    fn try_neg(self) -> Result<Self, NumberError> {
+        Ok(-self)
+    }
+
+    fn to_float(&self) -> Option<f64> {
+        ToPrimitive::to_f64(self)
+    }
+
+    fn from_bool(value: bool) -> Self {
+        BigInt::from(u8::from(value))
+    }
+
+    fn try_cmp(&self, other: &Self) -> Result<Option<Ordering>, NumberError> {
+        Ok(Some(self.cmp(other)))
+    }
+}
+
+/// Roots round towards zero, rounding functions are the identity.
+impl Elementary for BigInt {
+    fn sqrt(self) -> Result<Self, NumberError> {
+        if self.is_negative() {
+            return Err(NumberError::Domain(format!("square root of negative number {self}")));
+        }
+        Ok(Roots::sqrt(&self))
+    }
+
+    fn cbrt(self) -> Result<Self, NumberError> {
+        Ok(Roots::cbrt(&self))
+    }
+
+    fn abs(self) -> Result<Self, NumberError> {
+        Ok(Signed::abs(&self))
+    }
+
+    fn sign(self) -> Result<Self, NumberError> {
+        Ok(self.signum())
+    }
+
+    fn minimum(self, other: Self) -> Result<Self, NumberError> {
+        Ok(Ord::min(self, other))
+    }
+
+    fn maximum(self, other: Self) -> Result<Self, NumberError> {
+        Ok(Ord::max(self, other))
+    }
+
+    fn floor(self) -> Result<Self, NumberError> {
+        Ok(self)
+    }
+
+    fn ceil(self) -> Result<Self, NumberError> {
+        Ok(self)
+    }
+
+    fn round(self) -> Result<Self, NumberError> {
+        Ok(self)
+    }
+
+    fn trunc(self) -> Result<Self, NumberError> {
+        Ok(self)
+    }
+}
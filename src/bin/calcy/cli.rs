@@ -1,4 +1,8 @@
-use clap::Parser;
+use calcy::decimal::{Context, Decimal, Rounding};
+use calcy::format::{ComplexForm, Fraction, Notation};
+use calcy::parse::Identifiers;
+use clap::{Parser, ValueEnum};
+use num::{BigInt, Signed};
 use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
 
@@ -9,9 +13,24 @@ pub enum PossibleDataType {
     u8,
     u16,
     u32,
+    u64,
+    u128,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
     f32,
     f64,
     decimal,
+    bigdecimal,
+    rational,
+    complex,
+    bigint,
+    fixed,
+    modular,
+    interval,
+    measured,
 }
 
 impl Display for PossibleDataType {
@@ -20,6 +39,168 @@ impl Display for PossibleDataType {
     }
 }
 
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum PossibleIdentifiers {
+    /// Every letter is a variable, `2ab` is `2*a*b`
+    letters,
+    /// Whole words are names, `rate*time` uses two variables
+    words,
+}
+
+impl Display for PossibleIdentifiers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl From<PossibleIdentifiers> for Identifiers {
+    fn from(value: PossibleIdentifiers) -> Self {
+        match value {
+            PossibleIdentifiers::letters => Identifiers::Letters,
+            PossibleIdentifiers::words => Identifiers::Words,
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum PossibleOverflow {
+    /// Report an error when a result does not fit
+    checked,
+    /// Drop the bits that do not fit, '255+1' is 0 for u8
+    wrapping,
+    /// Clamp to the bounds of the datatype, '255+1' is 255 for u8
+    saturating,
+}
+
+impl Display for PossibleOverflow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum PossibleRounding {
+    /// To the nearest value, ties to the even neighbour
+    half_even,
+    /// To the nearest value, ties away from zero
+    half_up,
+    /// Towards zero
+    down,
+    /// Away from zero
+    up,
+    /// Towards positive infinity
+    ceiling,
+    /// Towards negative infinity
+    floor,
+}
+
+impl Display for PossibleRounding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_possible_value().unwrap().get_name())
+    }
+}
+
+impl From<PossibleRounding> for Rounding {
+    fn from(value: PossibleRounding) -> Self {
+        match value {
+            PossibleRounding::half_even => Rounding::HalfEven,
+            PossibleRounding::half_up => Rounding::HalfUp,
+            PossibleRounding::down => Rounding::Down,
+            PossibleRounding::up => Rounding::Up,
+            PossibleRounding::ceiling => Rounding::Ceiling,
+            PossibleRounding::floor => Rounding::Floor,
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum PossibleNotation {
+    /// Results as the datatype prints them
+    plain,
+    /// A fixed number of decimals, 2 unless '--digits' is given
+    fixed,
+    /// A number of significant digits, 6 unless '--digits' is given
+    significant,
+    /// One integer digit and an exponent, '1.5e-7'
+    scientific,
+    /// An exponent that is a multiple of three, '150e-9'
+    engineering,
+}
+
+impl Display for PossibleNotation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl PossibleNotation {
+    /// The notation with the given number of digits, or the default for the notation.
+    pub fn with_digits(self, digits: Option<usize>) -> Notation {
+        match self {
+            PossibleNotation::plain => Notation::Plain,
+            PossibleNotation::fixed => Notation::Fixed(digits.unwrap_or(2)),
+            PossibleNotation::significant => Notation::Significant(digits.unwrap_or(6).max(1)),
+            PossibleNotation::scientific => Notation::Scientific(digits.map(|d| d.max(1))),
+            PossibleNotation::engineering => Notation::Engineering(digits.map(|d| d.max(1))),
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum PossibleFraction {
+    /// In lowest terms, '7/2'
+    reduced,
+    /// An integer and a proper fraction, '3 1/2'
+    mixed,
+    /// Decimal digits with the repeating part in parentheses, '0.1(6)'
+    decimal,
+}
+
+impl Display for PossibleFraction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl From<PossibleFraction> for Fraction {
+    fn from(value: PossibleFraction) -> Self {
+        match value {
+            PossibleFraction::reduced => Fraction::Reduced,
+            PossibleFraction::mixed => Fraction::Mixed,
+            PossibleFraction::decimal => Fraction::Decimal,
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum PossibleComplexForm {
+    /// Real and imaginary part, '1+2i'
+    rectangular,
+    /// Magnitude and angle in radians, '2.236∠1.107'
+    polar,
+}
+
+impl Display for PossibleComplexForm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl From<PossibleComplexForm> for ComplexForm {
+    fn from(value: PossibleComplexForm) -> Self {
+        match value {
+            PossibleComplexForm::rectangular => ComplexForm::Rectangular,
+            PossibleComplexForm::polar => ComplexForm::Polar,
+        }
+    }
+}
+
 /// Evaluate simple algebraic equations fast, that's it!
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -43,7 +224,75 @@ pub struct Args {
     #[arg(short, long, default_value_t = PossibleDataType::f64)]
     pub datatype: PossibleDataType,
 
+    /// How integer datatypes handle results that do not fit
+    #[arg(long, default_value_t = PossibleOverflow::checked)]
+    pub overflow: PossibleOverflow,
+
+    /// Show integer results in decimal, hexadecimal and binary, toggled with 'programmer' in the REPL
+    #[arg(short, long, default_value_t = false)]
+    pub programmer: bool,
+
     /// Evaluate expressions with an exact (decimal) datatype, alias for '--datatype decimal'
     #[arg(short, long, default_value_t = false)]
     pub exact: bool,
+
+    /// Significant digits kept by decimal multiplication, division and powers, at most 38 for decimal
+    #[arg(long, default_value_t = Decimal::MAX_SCALE as u32, value_parser = clap::value_parser!(u32).range(1..))]
+    pub precision: u32,
+
+    /// Evaluate integers modulo this number, implies '--datatype modular'
+    #[arg(long, value_parser = parse_modulus)]
+    pub modulus: Option<BigInt>,
+
+    /// Fractional digits of the fixed datatype, at most 38
+    #[arg(long, value_parser = clap::value_parser!(u8).range(0..=38))]
+    pub scale: Option<u8>,
+
+    /// How decimal and fixed results are rounded to the precision
+    #[arg(long, default_value_t = PossibleRounding::half_even)]
+    pub rounding: PossibleRounding,
+
+    /// Digits a bigdecimal or bigint result or a rational power may have before evaluation fails with an overflow
+    #[arg(long, default_value_t = Context::default().max_digits)]
+    pub max_digits: u64,
+
+    /// How results are written, changed with 'format <notation> [digits]' in the REPL
+    #[arg(long, default_value_t = PossibleNotation::plain)]
+    pub notation: PossibleNotation,
+
+    /// Decimals for fixed, significant digits for the other notations
+    #[arg(long)]
+    pub digits: Option<usize>,
+
+    /// Separate groups of three integer digits with this character, changed with 'grouping <char>|off' in the REPL
+    #[arg(long)]
+    pub grouping: Option<char>,
+
+    /// The character between the integer and fractional digits, changed with 'mark <char>' in the REPL
+    #[arg(long, default_value_t = '.')]
+    pub decimal_mark: char,
+
+    /// How rational results are written, changed with 'fraction <style>' in the REPL
+    #[arg(long, default_value_t = PossibleFraction::reduced)]
+    pub fraction: PossibleFraction,
+
+    /// How complex results are written, changed with 'complex <form>' in the REPL
+    #[arg(long, default_value_t = PossibleComplexForm::rectangular)]
+    pub complex_form: PossibleComplexForm,
+
+    /// How letters are grouped into variable and function names
+    #[arg(long, default_value_t = PossibleIdentifiers::letters)]
+    pub identifiers: PossibleIdentifiers,
+
+    /// Require an explicit '*' between operands, '2a' becomes an error
+    #[arg(long, default_value_t = false)]
+    pub no_implicit_multiplication: bool,
+}
+
+fn parse_modulus(value: &str) -> Result<BigInt, String> {
+    match value.parse::<BigInt>() {
+        Ok(modulus) if modulus.is_positive() => Ok(modulus),
+        Ok(_) => Err("the modulus must be positive".into()),
+        Err(error) => Err(error.to_string()),
+    }
 }
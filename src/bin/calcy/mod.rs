@@ -1,15 +1,27 @@
-use crate::cli::{Args, PossibleDataType};
-use calcy::decimal::Decimal;
-use clap::Parser;
+use crate::cli::{Args, PossibleComplexForm, PossibleDataType, PossibleFraction, PossibleNotation, PossibleOverflow};
+use calcy::big_decimal::BigDecimal;
+use calcy::builtins::Elementary;
+use calcy::decimal::{Context, Decimal};
+use calcy::dual::Dual;
+use calcy::eval::EvalError;
+use calcy::fixed::Fixed;
+use calcy::format::{ComplexForm, Format, Fraction, Notation};
+use calcy::function::Functions;
+use calcy::interval::Interval;
+use calcy::measured::Measured;
+use calcy::modular::Modular;
+use calcy::parse::Syntax;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, ValueEnum};
+use num::complex::Complex64;
+use num::{BigInt, BigRational};
 use console::style;
 use log::{debug, warn};
-use num::traits::Pow;
 use rustyline::error::ReadlineError;
 use rustyline::DefaultEditor;
 use std::collections::HashMap;
 use std::fmt::{Debug, Display};
-use std::ops::{Add, Div, Mul, Rem, Sub};
-use std::str::FromStr;
+use std::num::{Saturating, Wrapping};
 use std::time::Instant;
 use std::{fs, process};
 
@@ -28,47 +40,141 @@ fn main() {
         args.datatype = PossibleDataType::decimal;
     }
 
+    if args.modulus.is_some() {
+        args.datatype = PossibleDataType::modular;
+    }
+
+    calcy::decimal::set_context(Context {
+        precision: args.precision,
+        rounding: args.rounding.into(),
+        max_digits: args.max_digits,
+    });
+
     match args.datatype {
-        PossibleDataType::usize => calcy::<usize>(args),
-        PossibleDataType::u8 => calcy::<u8>(args),
-        PossibleDataType::u16 => calcy::<u16>(args),
-        PossibleDataType::u32 => calcy::<u32>(args),
+        PossibleDataType::usize => integer::<usize>(args),
+        PossibleDataType::u8 => integer::<u8>(args),
+        PossibleDataType::u16 => integer::<u16>(args),
+        PossibleDataType::u32 => integer::<u32>(args),
+        PossibleDataType::u64 => integer::<u64>(args),
+        PossibleDataType::u128 => integer::<u128>(args),
+        PossibleDataType::i8 => integer::<i8>(args),
+        PossibleDataType::i16 => integer::<i16>(args),
+        PossibleDataType::i32 => integer::<i32>(args),
+        PossibleDataType::i64 => integer::<i64>(args),
+        PossibleDataType::i128 => integer::<i128>(args),
         PossibleDataType::f32 => calcy::<f32>(args),
         PossibleDataType::f64 => calcy::<f64>(args),
         PossibleDataType::decimal => calcy::<Decimal>(args),
+        PossibleDataType::bigdecimal => calcy::<BigDecimal>(args),
+        PossibleDataType::rational => calcy::<BigRational>(args),
+        PossibleDataType::complex => calcy::<Complex64>(args),
+        PossibleDataType::bigint => calcy::<BigInt>(args),
+        PossibleDataType::fixed => fixed(args),
+        PossibleDataType::modular => match args.modulus.clone() {
+            Some(modulus) => {
+                calcy::modular::set_modulus(modulus);
+                calcy::<Modular>(args)
+            }
+            None => Args::command().error(ErrorKind::MissingRequiredArgument, "the modular datatype needs a --modulus").exit(),
+        },
+        PossibleDataType::interval => calcy::<Interval>(args),
+        PossibleDataType::measured => calcy::<Measured>(args),
     }
 }
 
-trait TypeConstraint<T>: Debug + Display + FromStr + Copy + PartialEq + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T> + Pow<T, Output = T> + Rem<Output = T> {}
-impl<T: Debug + Display + FromStr + Copy + PartialEq + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T> + Pow<T, Output = T> +  Rem<Output = T>> TypeConstraint<T> for T {}
+trait TypeConstraint: Elementary + 'static + Debug + Display + Clone {}
+impl<T: Elementary + 'static + Debug + Display + Clone> TypeConstraint for T {}
+
+/// Runs an integer datatype with the selected overflow policy.
+fn integer<T>(args: Args)
+where
+    T: TypeConstraint,
+    Wrapping<T>: TypeConstraint,
+    Saturating<T>: TypeConstraint,
+{
+    match args.overflow {
+        PossibleOverflow::checked => calcy::<T>(args),
+        PossibleOverflow::wrapping => calcy::<Wrapping<T>>(args),
+        PossibleOverflow::saturating => calcy::<Saturating<T>>(args),
+    }
+}
+
+/// Runs the fixed datatype, every scale is a type of its own.
+fn fixed(args: Args) {
+    macro_rules! scales {
+        ($($scale:literal),*) => {
+            match args.scale.unwrap_or(DEFAULT_SCALE) {
+                $($scale => calcy::<Fixed<$scale>>(args),)*
+                scale => unreachable!("scale {scale} is rejected by the argument parser"),
+            }
+        };
+    }
+    scales!(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38)
+}
+
+/// The scale of the fixed datatype unless `--scale` is given, enough for cents.
+const DEFAULT_SCALE: u8 = 2;
+
+/// The state shared by all statements evaluated in one invocation.
+struct Session<T> {
+    variables: HashMap<String, T>,
+    functions: Functions<T>,
+    syntax: Syntax,
+    benchmark: bool,
+    /// Whether integer results are also shown in hexadecimal and binary.
+    programmer: bool,
+    format: Format,
+}
 
 fn calcy<T>(args: Args)
 where
-    T: TypeConstraint<T>
+    T: TypeConstraint
 {
-    let mut variables: HashMap<String, T> = HashMap::new();
+    let mut session: Session<T> = Session {
+        variables: HashMap::new(),
+        functions: Functions::builtins(),
+        syntax: Syntax {
+            identifiers: args.identifiers.into(),
+            implicit_multiplication: !args.no_implicit_multiplication,
+        },
+        benchmark: args.benchmark,
+        programmer: args.programmer,
+        format: Format {
+            notation: args.notation.with_digits(args.digits),
+            separator: args.grouping,
+            decimal_mark: args.decimal_mark,
+            fraction: args.fraction.into(),
+            complex: args.complex_form.into(),
+        },
+    };
+    if !matches!(args.overflow, PossibleOverflow::checked) && T::from_bool(false).to_bits().is_none() {
+        warn!("Overflow policy {} has no effect on datatype {}", args.overflow, args.datatype);
+    }
+    if args.scale.is_some() && !matches!(args.datatype, PossibleDataType::fixed) {
+        warn!("Scale has no effect on datatype {}", args.datatype);
+    }
     let mut exit_code = 0;
 
     if let Some(file_path) = args.file {
         debug!("Attempting to read from file {}", file_path.display());
         let contents = fs::read_to_string(file_path).expect("could not read from file");
         let lines = contents.lines();
-        lines.for_each(|l| interpret_statement(l.into(), args.benchmark, &mut variables, &mut exit_code));
+        lines.for_each(|l| interpret_statement(l.into(), &mut session, &mut exit_code));
     }
 
-    args.equations.into_iter().for_each(|e| interpret_statement(e, args.benchmark, &mut variables, &mut exit_code));
+    args.equations.into_iter().for_each(|e| interpret_statement(e, &mut session, &mut exit_code));
 
     if args.interactive {
         println!("Calcy (v{}), have fun!", env!("CARGO_PKG_VERSION"));
-        repl(&mut variables, args.benchmark);
+        repl(&mut session);
         exit_code = 0;
     }
     process::exit(exit_code);
 }
 
-fn interpret_statement<T>(statement: String, benchmark: bool, variables: &mut HashMap<String, T>, exit_code: &mut i32)
+fn interpret_statement<T>(statement: String, session: &mut Session<T>, exit_code: &mut i32)
 where
-    T: TypeConstraint<T>,
+    T: TypeConstraint,
 {
     if statement.to_lowercase() == "exit" {
         println!("Exiting...");
@@ -76,52 +182,208 @@ where
     }
 
     if statement.to_lowercase() == "vars" {
-        println!("{:?}", variables);
+        println!("{:?}", session.variables);
+        return;
+    }
+
+    if statement.to_lowercase() == "funcs" {
+        let mut names = session.functions.names().collect::<Vec<_>>();
+        names.sort();
+        println!("{names:?}");
+        return;
+    }
+
+    if statement.to_lowercase() == "programmer" {
+        session.programmer = !session.programmer;
+        println!("Programmer mode {}", if session.programmer { "on" } else { "off" });
+        return;
+    }
+
+    if let Some(command) = format_command(&statement, &mut session.format) {
+        match command {
+            Ok(()) => println!("{}", describe(&session.format)),
+            Err(message) => eprintln!("{}", style(format!("error: {message}")).red()),
+        }
+        return;
+    }
+
+    if let Some(position) = assignment(&statement) {
+        retrieve_variable(&statement, position, session);
         return;
     }
 
-    if statement.contains('=') {
-        retrieve_variable(&statement, variables);
+    if let Some((variable, expression)) = derivative_command(&statement) {
+        derivative(variable, expression, session, exit_code);
         return;
     }
 
-    eval(statement, benchmark, variables, exit_code);
+    eval(statement, session, exit_code);
+}
+
+/// Applies the `format`, `grouping`, `mark`, `fraction` and `complex` commands, `None` if the statement is none of them.
+fn format_command(statement: &str, format: &mut Format) -> Option<Result<(), String>> {
+    let words = statement.split_whitespace().collect::<Vec<_>>();
+    let single = |word: &str| {
+        let mut chars = word.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(format!("expected a single character, found '{word}'")),
+        }
+    };
+    let result = match words.as_slice() {
+        ["format"] => Ok(()),
+        ["format", notation, rest @ ..] if rest.len() <= 1 => {
+            let notation = PossibleNotation::from_str(notation, true).map_err(|_| format!("unknown notation '{notation}'"));
+            let digits = rest.first().map(|d| d.parse::<usize>().map_err(|_| format!("invalid number of digits '{d}'"))).transpose();
+            notation.and_then(|notation| digits.map(|digits| format.notation = notation.with_digits(digits)))
+        }
+        ["grouping", "off"] => {
+            format.separator = None;
+            Ok(())
+        }
+        ["grouping", separator] => single(separator).map(|c| format.separator = Some(c)),
+        ["mark", mark] => single(mark).map(|c| format.decimal_mark = c),
+        ["complex", form] => PossibleComplexForm::from_str(form, true).map(|form| format.complex = form.into()).map_err(|_| format!("unknown complex form '{form}'")),
+        ["fraction", style] => PossibleFraction::from_str(style, true).map(|style| format.fraction = style.into()).map_err(|_| format!("unknown fraction style '{style}'")),
+        _ => return None,
+    };
+    Some(result)
 }
 
-fn retrieve_variable<T>(input: &str, variables: &mut HashMap<String, T>)
+/// A short summary of the format like `fixed 2, grouping ',', mark '.', fraction reduced, complex rectangular`.
+fn describe(format: &Format) -> String {
+    let notation = match format.notation {
+        Notation::Plain => "plain".to_string(),
+        Notation::Fixed(digits) => format!("fixed {digits}"),
+        Notation::Significant(digits) => format!("significant {digits}"),
+        Notation::Scientific(digits) => digits.map_or("scientific".into(), |digits| format!("scientific {digits}")),
+        Notation::Engineering(digits) => digits.map_or("engineering".into(), |digits| format!("engineering {digits}")),
+    };
+    let grouping = format.separator.map_or("off".into(), |c| format!("'{c}'"));
+    let fraction = match format.fraction {
+        Fraction::Reduced => "reduced",
+        Fraction::Mixed => "mixed",
+        Fraction::Decimal => "decimal",
+    };
+    let complex = match format.complex {
+        ComplexForm::Rectangular => "rectangular",
+        ComplexForm::Polar => "polar",
+    };
+    format!("Format {notation}, grouping {grouping}, mark '{}', fraction {fraction}, complex {complex}", format.decimal_mark)
+}
+
+/// The position of the `=` assigning a variable, the `=` of comparisons like `==` or `<=` is skipped.
+fn assignment(statement: &str) -> Option<usize> {
+    let bytes = statement.as_bytes();
+    (0..bytes.len()).find(|&i| {
+        let before = i.checked_sub(1).map(|j| bytes[j]);
+        bytes[i] == b'=' && bytes.get(i + 1) != Some(&b'=') && !matches!(before, Some(b'=' | b'!' | b'<' | b'>'))
+    })
+}
+
+fn retrieve_variable<T>(input: &str, position: usize, session: &mut Session<T>)
 where
-    T: TypeConstraint<T>,
+    T: TypeConstraint,
 {
-    let (name, value) = input.split_once('=').unwrap();
-    variables.insert(name.into(), calcy::solve_vars_with(value.into(), variables).unwrap());
+    let (name, value) = (&input[..position], &input[position + 1..]);
+    match calcy::solve_with_syntax(value.into(), &session.variables, &session.functions, &session.syntax) {
+        Ok(v) => {
+            session.variables.insert(name.trim().into(), v);
+        }
+        Err(e) => report(input, name.len() + 1, &e),
+    }
 }
 
-fn eval<T>(equation: String, benchmark: bool, variables: &mut HashMap<String, T>, exit_code: &mut i32)
+fn eval<T>(equation: String, session: &mut Session<T>, exit_code: &mut i32)
 where
-    T: TypeConstraint<T>,
+    T: TypeConstraint,
 {
     let start = Instant::now();
-    let result = calcy::solve_vars_with::<T>(equation, variables);
+    let result = calcy::solve_with_syntax::<T>(equation.clone(), &session.variables, &session.functions, &session.syntax);
     let duration = start.elapsed();
     match result {
         Ok(r) => {
-            if benchmark {
-                println!("{r} (took {}μs)", duration.as_micros());
+            let output = match (r.to_bits(), r.to_ratio(), r.to_complex()) {
+                (Some((value, bits)), _, _) if session.programmer => programmer(&r, value, bits),
+                (_, Some(ratio), _) => session.format.ratio(&ratio),
+                (_, _, Some(complex)) => session.format.complex(&complex),
+                _ => session.format.apply(&r),
+            };
+            if session.benchmark {
+                println!("{output} (took {}μs)", duration.as_micros());
             } else {
-                println!("{r}");
+                println!("{output}");
             }
-            variables.insert("ans".into(), r);
+            session.variables.insert("ans".into(), r);
         }
         Err(e) => {
-            eprintln!("{}", style(format!("error: {e}")).red());
+            report(&equation, 0, &e);
             *exit_code = 1;
         }
     }
 }
 
-fn repl<T>(variables: &mut HashMap<String, T>, benchmark: bool)
+/// Prints the value and the derivative by `variable` of the expression, `derivative x x^2 + 1`.
+///
+/// The session's variables are converted to `f64`, using one that is no real number like `1+2i` is an error.
+fn derivative<T>(variable: &str, expression: &str, session: &Session<T>, exit_code: &mut i32)
+where
+    T: TypeConstraint,
+{
+    let variables = session
+        .variables
+        .iter()
+        .filter_map(|(name, value)| value.to_float().map(|value| (name.clone(), value)))
+        .map(|(name, value)| (name.clone(), if name == variable { Dual::variable(value) } else { Dual::constant(value) }))
+        .collect();
+    let result = calcy::solve_with_syntax::<Dual>(expression.into(), &variables, &Functions::builtins(), &session.syntax).map_err(|e| match e {
+        // a variable of the session that is missing could not be converted
+        calcy::Error::EvalError(EvalError::VariableNotFound(name, span)) if session.variables.contains_key(&name) => {
+            let message = format!("{name} = {} is not a real number", session.variables[&name]);
+            calcy::Error::EvalError(EvalError::Domain(message, span))
+        }
+        e => e,
+    });
+    match result {
+        Ok(result) => println!("{}, d/d{variable} = {}", session.format.apply(&result.value()), session.format.apply(&result.derivative())),
+        Err(e) => {
+            report(expression, 0, &e);
+            *exit_code = 1;
+        }
+    }
+}
+
+/// Splits `derivative <variable> <expression>` into the variable and the expression, `None` for any other statement.
+fn derivative_command(statement: &str) -> Option<(&str, &str)> {
+    let (keyword, rest) = statement.trim_start().split_once(char::is_whitespace)?;
+    let (variable, expression) = rest.trim_start().split_once(char::is_whitespace)?;
+    let identifier = variable.starts_with(|c: char| c.is_alphabetic() || c == '_') && variable.chars().all(|c| c.is_alphanumeric() || c == '_');
+    (keyword == "derivative" && identifier).then_some((variable, expression))
+}
+
+/// Shows the number followed by its bits in hexadecimal and binary, padded to the bit width of its type.
+fn programmer(number: impl Display, value: u128, bits: u32) -> String {
+    let hex = format!("{value:0width$x}", width = bits as usize / 4);
+    let binary = format!("{value:0width$b}", width = bits as usize);
+    let nibbles = binary.as_bytes().chunks(4).map(|nibble| String::from_utf8_lossy(nibble)).collect::<Vec<_>>();
+    format!("{number}  0x{hex}  0b{}  ({bits} bit)", nibbles.join("_"))
+}
+
+/// Prints the error followed by the input with the offending range underlined,
+/// `offset` is the position of the evaluated expression within `input`.
+fn report(input: &str, offset: usize, error: &calcy::Error) {
+    eprintln!("{}", style(format!("error: {error}")).red());
+    let span = error.span();
+    let (start, end) = ((offset + span.start).min(input.len()), (offset + span.end).min(input.len()));
+    let padding = input[..start].chars().count();
+    let width = input[start..end].chars().count().max(1);
+    eprintln!("  {input}");
+    eprintln!("  {}{}", " ".repeat(padding), style(format!("^{}", "~".repeat(width - 1))).red());
+}
+
+fn repl<T>(session: &mut Session<T>)
 where
-    T: TypeConstraint<T>,
+    T: TypeConstraint,
 {
     let mut rl = DefaultEditor::new().expect("cannot start repl");
     let history_path = std::env::temp_dir().join("calcy-history.txt");
@@ -135,7 +397,7 @@ where
             Ok(line) => {
                 rl.add_history_entry(line.as_str()).expect("could not add history entry");
                 rl.save_history(&history_path).expect("could not save history");
-                interpret_statement(line, benchmark, variables, &mut 0);
+                interpret_statement(line, session, &mut 0);
             }
             Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => {
                 println!("Exiting...");
@@ -0,0 +1,321 @@
+use crate::dual::Dual;
+use crate::function::{Arity, Functions};
+use crate::measured::Measured;
+use crate::number::{Number, NumberError};
+use std::f64::consts::{E, LOG10_E, LOG2_E, PI};
+use std::num::{Saturating, Wrapping};
+
+macro_rules! unsupported {
+    ($($name:ident),*) => {$(
+        fn $name(self) -> Result<Self, NumberError> {
+            Err(NumberError::Unsupported(stringify!($name)))
+        }
+    )*};
+}
+
+/// The elementary functions callable from expressions, every function defaults to
+/// [`NumberError::Unsupported`] so a type only implements the ones that apply to it.
+pub trait Elementary: Number {
+    unsupported!(sqrt, cbrt, exp, ln, log10, log2, sin, cos, tan, asin, acos, atan, sinh, cosh, tanh, asinh, acosh, atanh, abs, sign, floor, ceil, round, trunc, re, im, arg, conj);
+
+    /// The logarithm of `self` to the given `base`.
+    fn log(self, _base: Self) -> Result<Self, NumberError> {
+        Err(NumberError::Unsupported("log"))
+    }
+
+    fn minimum(self, _other: Self) -> Result<Self, NumberError> {
+        Err(NumberError::Unsupported("min"))
+    }
+
+    fn maximum(self, _other: Self) -> Result<Self, NumberError> {
+        Err(NumberError::Unsupported("max"))
+    }
+
+    /// The constant `π`, registered as a function without arguments so expressions can name it like a variable.
+    fn pi() -> Result<Self, NumberError> {
+        Err(NumberError::Unsupported("pi"))
+    }
+
+    /// Euler's number, see [`Elementary::pi`].
+    fn e() -> Result<Self, NumberError> {
+        Err(NumberError::Unsupported("e"))
+    }
+}
+
+macro_rules! register_unary {
+    ($functions:ident, $($name:ident),*) => {$(
+        $functions.register(stringify!($name), Arity::Exact(1), |args: &[T]| args[0].clone().$name());
+    )*};
+}
+
+impl<T: Elementary + Clone + 'static> Functions<T> {
+    /// Creates a registry containing every elementary function.
+    pub fn builtins() -> Self {
+        let mut functions = Self::new();
+        register_unary!(functions, sqrt, cbrt, exp, ln, log10, log2, sin, cos, tan, asin, acos, atan, sinh, cosh, tanh, asinh, acosh, atanh, abs, sign, floor, ceil, round, trunc, re, im, arg, conj);
+        functions
+            .register("log", Arity::Exact(2), |args: &[T]| args[1].clone().log(args[0].clone()))
+            .register("min", Arity::AtLeast(1), |args: &[T]| args[1..].iter().try_fold(args[0].clone(), |a, b| a.minimum(b.clone())))
+            .register("max", Arity::AtLeast(1), |args: &[T]| args[1..].iter().try_fold(args[0].clone(), |a, b| a.maximum(b.clone())))
+            .register("pi", Arity::Exact(0), |_: &[T]| T::pi())
+            .register("e", Arity::Exact(0), |_: &[T]| T::e());
+        functions
+    }
+}
+
+macro_rules! forward {
+    ($($name:ident),*) => {$(
+        fn $name(self) -> Result<Self, NumberError> {
+            Ok(<Self>::$name(self))
+        }
+    )*};
+}
+
+macro_rules! impl_float {
+    ($($t:ident),*) => {$(
+        impl Elementary for $t {
+            forward!(sqrt, cbrt, exp, ln, log10, log2, sin, cos, tan, asin, acos, atan, sinh, cosh, tanh, asinh, acosh, atanh, abs, floor, ceil, round, trunc);
+
+            fn sign(self) -> Result<Self, NumberError> {
+                Ok(if self == 0.0 { 0.0 } else { self.signum() })
+            }
+
+            fn log(self, base: Self) -> Result<Self, NumberError> {
+                Ok(<$t>::log(self, base))
+            }
+
+            fn minimum(self, other: Self) -> Result<Self, NumberError> {
+                Ok(<$t>::min(self, other))
+            }
+
+            fn maximum(self, other: Self) -> Result<Self, NumberError> {
+                Ok(<$t>::max(self, other))
+            }
+
+            fn pi() -> Result<Self, NumberError> {
+                Ok(std::$t::consts::PI)
+            }
+
+            fn e() -> Result<Self, NumberError> {
+                Ok(std::$t::consts::E)
+            }
+        }
+    )*};
+}
+
+macro_rules! identity {
+    ($($name:ident),*) => {$(
+        fn $name(self) -> Result<Self, NumberError> {
+            Ok(self)
+        }
+    )*};
+}
+
+/// Integer roots and logarithms round down, rounding functions are the identity.
+macro_rules! impl_unsigned {
+    ($($t:ty),*) => {$(
+        impl Elementary for $t {
+            fn sqrt(self) -> Result<Self, NumberError> {
+                Ok(self.isqrt())
+            }
+
+            fn cbrt(self) -> Result<Self, NumberError> {
+                let mut root = (self as f64).cbrt() as $t;
+                while root.checked_pow(3).is_none_or(|cube| cube > self) {
+                    root -= 1;
+                }
+                while (root + 1).checked_pow(3).is_some_and(|cube| cube <= self) {
+                    root += 1;
+                }
+                Ok(root)
+            }
+
+            fn log10(self) -> Result<Self, NumberError> {
+                self.checked_ilog10().map(|l| l as $t).ok_or_else(|| NumberError::Domain("logarithm of zero".into()))
+            }
+
+            fn log2(self) -> Result<Self, NumberError> {
+                self.checked_ilog2().map(|l| l as $t).ok_or_else(|| NumberError::Domain("logarithm of zero".into()))
+            }
+
+            fn log(self, base: Self) -> Result<Self, NumberError> {
+                if base < 2 {
+                    return Err(NumberError::Domain(format!("logarithm to base {base}")));
+                }
+                self.checked_ilog(base).map(|l| l as $t).ok_or_else(|| NumberError::Domain("logarithm of zero".into()))
+            }
+
+            fn sign(self) -> Result<Self, NumberError> {
+                Ok(self.min(1))
+            }
+
+            fn minimum(self, other: Self) -> Result<Self, NumberError> {
+                Ok(Ord::min(self, other))
+            }
+
+            fn maximum(self, other: Self) -> Result<Self, NumberError> {
+                Ok(Ord::max(self, other))
+            }
+
+            identity!(abs, floor, ceil, round, trunc);
+        }
+    )*};
+}
+
+/// Like the unsigned integers, roots of negative numbers round towards zero and `abs` is checked.
+macro_rules! impl_signed {
+    ($($t:ty),*) => {$(
+        impl Elementary for $t {
+            fn sqrt(self) -> Result<Self, NumberError> {
+                if self < 0 {
+                    return Err(NumberError::Domain(format!("square root of negative number {self}")));
+                }
+                Ok(self.isqrt())
+            }
+
+            fn cbrt(self) -> Result<Self, NumberError> {
+                Ok(self.unsigned_abs().cbrt()? as $t * self.signum())
+            }
+
+            fn log10(self) -> Result<Self, NumberError> {
+                self.checked_ilog10().map(|l| l as $t).ok_or_else(|| NumberError::Domain(format!("logarithm of {self}")))
+            }
+
+            fn log2(self) -> Result<Self, NumberError> {
+                self.checked_ilog2().map(|l| l as $t).ok_or_else(|| NumberError::Domain(format!("logarithm of {self}")))
+            }
+
+            fn log(self, base: Self) -> Result<Self, NumberError> {
+                if base < 2 {
+                    return Err(NumberError::Domain(format!("logarithm to base {base}")));
+                }
+                self.checked_ilog(base).map(|l| l as $t).ok_or_else(|| NumberError::Domain(format!("logarithm of {self}")))
+            }
+
+            fn abs(self) -> Result<Self, NumberError> {
+                self.checked_abs().ok_or(NumberError::Overflow)
+            }
+
+            fn sign(self) -> Result<Self, NumberError> {
+                Ok(self.signum())
+            }
+
+            fn minimum(self, other: Self) -> Result<Self, NumberError> {
+                Ok(Ord::min(self, other))
+            }
+
+            fn maximum(self, other: Self) -> Result<Self, NumberError> {
+                Ok(Ord::max(self, other))
+            }
+
+            identity!(floor, ceil, round, trunc);
+        }
+    )*};
+}
+
+macro_rules! delegate {
+    ($wrapper:ident, $($name:ident),*) => {$(
+        fn $name(self) -> Result<Self, NumberError> {
+            Elementary::$name(self.0).map($wrapper)
+        }
+    )*};
+}
+
+/// The overflow policies only change arithmetic, functions behave like on the plain integer.
+macro_rules! impl_wrapped {
+    ($wrapper:ident: $($t:ty),*) => {$(
+        impl Elementary for $wrapper<$t> {
+            delegate!($wrapper, sqrt, cbrt, exp, ln, log10, log2, sin, cos, tan, asin, acos, atan, sinh, cosh, tanh, asinh, acosh, atanh, abs, sign, floor, ceil, round, trunc, re, im, arg, conj);
+
+            fn log(self, base: Self) -> Result<Self, NumberError> {
+                Elementary::log(self.0, base.0).map($wrapper)
+            }
+
+            fn minimum(self, other: Self) -> Result<Self, NumberError> {
+                self.0.minimum(other.0).map($wrapper)
+            }
+
+            fn maximum(self, other: Self) -> Result<Self, NumberError> {
+                self.0.maximum(other.0).map($wrapper)
+            }
+        }
+    )*};
+}
+
+/// A value carrying first order derivatives, which functions update with the chain rule.
+pub(crate) trait Chain: Sized {
+    /// The value derivatives are taken at.
+    fn value(&self) -> f64;
+
+    /// A value without derivatives.
+    fn constant(value: f64) -> Self;
+
+    /// Applies a function with the given value and derivative at [`Chain::value`].
+    fn chain(&self, value: f64, derivative: f64) -> Self;
+}
+
+macro_rules! chain {
+    ($($name:ident: $f:expr, $derivative:expr;)*) => {$(
+        fn $name(self) -> Result<Self, NumberError> {
+            let x = Chain::value(&self);
+            Ok(self.chain($f(x), $derivative(x)))
+        }
+    )*};
+}
+
+/// Functions of values with derivatives apply their own derivative at the value, functions without one
+/// like `floor` are unsupported. Values are ordered by [`Chain::value`].
+macro_rules! impl_chain {
+    ($($t:ty),*) => {$(
+        impl Elementary for $t {
+            chain!(
+                sqrt: f64::sqrt, |x: f64| 0.5 / x.sqrt();
+                cbrt: f64::cbrt, |x: f64| 1.0 / (3.0 * x.cbrt() * x.cbrt());
+                exp: f64::exp, f64::exp;
+                ln: f64::ln, |x: f64| 1.0 / x;
+                log10: f64::log10, |x: f64| LOG10_E / x;
+                log2: f64::log2, |x: f64| LOG2_E / x;
+                sin: f64::sin, f64::cos;
+                cos: f64::cos, |x: f64| -x.sin();
+                tan: f64::tan, |x: f64| 1.0 / (x.cos() * x.cos());
+                asin: f64::asin, |x: f64| 1.0 / (1.0 - x * x).sqrt();
+                acos: f64::acos, |x: f64| -1.0 / (1.0 - x * x).sqrt();
+                atan: f64::atan, |x: f64| 1.0 / (1.0 + x * x);
+                sinh: f64::sinh, f64::cosh;
+                cosh: f64::cosh, f64::sinh;
+                tanh: f64::tanh, |x: f64| 1.0 / (x.cosh() * x.cosh());
+                asinh: f64::asinh, |x: f64| 1.0 / (x * x + 1.0).sqrt();
+                acosh: f64::acosh, |x: f64| 1.0 / (x * x - 1.0).sqrt();
+                atanh: f64::atanh, |x: f64| 1.0 / (1.0 - x * x);
+                abs: f64::abs, f64::signum;
+            );
+
+            fn log(self, base: Self) -> Result<Self, NumberError> {
+                self.ln()?.try_div(base.ln()?)
+            }
+
+            fn minimum(self, other: Self) -> Result<Self, NumberError> {
+                Ok(if Chain::value(&other) < Chain::value(&self) { other } else { self })
+            }
+
+            fn maximum(self, other: Self) -> Result<Self, NumberError> {
+                Ok(if Chain::value(&other) > Chain::value(&self) { other } else { self })
+            }
+
+            fn pi() -> Result<Self, NumberError> {
+                Ok(<$t as Chain>::constant(PI))
+            }
+
+            fn e() -> Result<Self, NumberError> {
+                Ok(<$t as Chain>::constant(E))
+            }
+        }
+    )*};
+}
+
+impl_float!(f32, f64);
+impl_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_signed!(i8, i16, i32, i64, i128);
+impl_wrapped!(Wrapping: u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128);
+impl_wrapped!(Saturating: u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128);
+impl_chain!(Dual, Measured);
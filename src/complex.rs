@@ -0,0 +1,140 @@
+use crate::builtins::Elementary;
+use crate::number::{Number, NumberError};
+use crate::parse::Literal;
+use num::complex::Complex64;
+use num::Zero;
+use std::cmp::Ordering;
+
+fn real(value: f64) -> Complex64 {
+    Complex64::new(value, 0.0)
+}
+
+/// Complex numbers follow IEEE like the floating point types, dividing by zero results in infinite or `NaN` parts.
+///
+/// `i` and `j` are the imaginary unit, so they cannot name variables while this type is used.
+impl Number for Complex64 {
+    fn from_literal(literal: &Literal) -> Option<Self> {
+        f64::from_literal(literal).map(real)
+    }
+
+    fn try_add(self, rhs: Self) -> Result<Self, NumberError> {
+        Ok(self + rhs)
+    }
+
+    fn try_sub(self, rhs: Self) -> Result<Self, NumberError> {
+        Ok(self - rhs)
+    }
+
+    fn try_mul(self, rhs: Self) -> Result<Self, NumberError> {
+        Ok(self * rhs)
+    }
+
+    fn try_div(self, rhs: Self) -> Result<Self, NumberError> {
+        Ok(self / rhs)
+    }
+
+    /// The remainder of the quotient truncated part by part.
+    fn try_rem(self, rhs: Self) -> Result<Self, NumberError> {
+        Ok(self % rhs)
+    }
+
+    /// Real integer exponents multiply repeatedly, so `i^2` is exactly `-1`, any other exponent uses the principal value.
+    fn try_pow(self, rhs: Self) -> Result<Self, NumberError> {
+        if rhs.im == 0.0 && rhs.re.fract() == 0.0 && rhs.re.abs() <= i32::MAX as f64 {
+            return Ok(self.powi(rhs.re as i32));
+        }
+        Ok(self.powc(rhs))
+    }
+
+    /// Zero parts stay positive, so `sqrt(-4)` is `2i` instead of the `-2i` of the other side of the branch cut.
+    fn try_neg(self) -> Result<Self, NumberError> {
+        Ok(-self + Complex64::zero())
+    }
+
+    fn from_bool(value: bool) -> Self {
+        real(u8::from(value).into())
+    }
+
+    /// Only real values are ordered.
+    fn try_cmp(&self, other: &Self) -> Result<Option<Ordering>, NumberError> {
+        if self.im != 0.0 || other.im != 0.0 {
+            return Err(NumberError::Domain(format!("{self} and {other} cannot be ordered, one of them is not real")));
+        }
+        Ok(self.re.partial_cmp(&other.re))
+    }
+
+    fn try_eq(&self, other: &Self) -> Result<bool, NumberError> {
+        Ok(self == other)
+    }
+
+    fn imaginary_unit() -> Option<Self> {
+        Some(Complex64::i())
+    }
+
+    fn to_complex(&self) -> Option<Complex64> {
+        Some(*self)
+    }
+
+    fn to_float(&self) -> Option<f64> {
+        (self.im == 0.0).then_some(self.re)
+    }
+}
+
+macro_rules! forward {
+    ($($name:ident),*) => {$(
+        fn $name(self) -> Result<Self, NumberError> {
+            Ok(Complex64::$name(self))
+        }
+    )*};
+}
+
+macro_rules! parts {
+    ($($name:ident),*) => {$(
+        fn $name(self) -> Result<Self, NumberError> {
+            Ok(Complex64::new(self.re.$name(), self.im.$name()))
+        }
+    )*};
+}
+
+/// Functions use their principal values, rounding functions round the real and imaginary part separately.
+impl Elementary for Complex64 {
+    forward!(sqrt, cbrt, exp, ln, log10, log2, sin, cos, tan, asin, acos, atan, sinh, cosh, tanh, asinh, acosh, atanh);
+    parts!(floor, ceil, round, trunc);
+
+    fn log(self, base: Self) -> Result<Self, NumberError> {
+        Ok(self.ln() / base.ln())
+    }
+
+    fn abs(self) -> Result<Self, NumberError> {
+        Ok(real(self.norm()))
+    }
+
+    /// The value scaled to length one, zero stays zero.
+    fn sign(self) -> Result<Self, NumberError> {
+        Ok(if self.is_zero() { self } else { self / self.norm() })
+    }
+
+    fn pi() -> Result<Self, NumberError> {
+        Ok(real(std::f64::consts::PI))
+    }
+
+    fn e() -> Result<Self, NumberError> {
+        Ok(real(std::f64::consts::E))
+    }
+
+    fn re(self) -> Result<Self, NumberError> {
+        Ok(real(self.re))
+    }
+
+    fn im(self) -> Result<Self, NumberError> {
+        Ok(real(self.im))
+    }
+
+    fn arg(self) -> Result<Self, NumberError> {
+        Ok(real(Complex64::arg(self)))
+    }
+
+    fn conj(self) -> Result<Self, NumberError> {
+        Ok(Complex64::conj(&self))
+    }
+}
@@ -1,10 +1,20 @@
+use crate::builtins::Elementary;
+use crate::number::{Number, NumberError};
+use crate::parse::Literal;
+use crate::transcendental;
+use num::bigint::Sign;
 use num::traits::Pow;
-use num::FromPrimitive;
+use num::traits::{Bounded, CheckedAdd, CheckedDiv, CheckedMul, CheckedNeg, CheckedRem, CheckedSub, Num, NumCast};
+use num::{BigInt, FromPrimitive, Integer, One, Signed, ToPrimitive, Zero};
+use std::cell::Cell;
+use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
-use std::ops::{Add, Div, Mul, Rem, Sub};
+use std::hash::{Hash, Hasher};
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
 use std::str::FromStr;
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+/// A decimal `value * 10^-scale`, values are equal regardless of their scale, so `1.10 == 1.1`.
+#[derive(Debug, Copy, Clone)]
 pub struct Decimal {
     value: i128,
     scale: u8,
@@ -13,38 +23,423 @@ pub struct Decimal {
 #[derive(Debug, PartialEq)]
 pub struct ParseDecimalError {}
 
+/// How a result that cannot be represented exactly is rounded to the precision of the [`Context`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Rounding {
+    /// To the nearest value, ties go to the even neighbour.
+    #[default]
+    HalfEven,
+    /// To the nearest value, ties go away from zero.
+    HalfUp,
+    /// Towards zero.
+    Down,
+    /// Away from zero.
+    Up,
+    /// Towards positive infinity.
+    Ceiling,
+    /// Towards negative infinity.
+    Floor,
+}
+
+impl Rounding {
+    /// Divides `numerator` by the positive `denominator`, rounding the quotient to an integer.
+    pub(crate) fn divide(self, numerator: &BigInt, denominator: &BigInt) -> BigInt {
+        let (quotient, remainder) = numerator.div_rem(denominator);
+        if remainder.is_zero() {
+            return quotient;
+        }
+        let away = &quotient + numerator.signum();
+        let half = (Signed::abs(&remainder) * 2u8).cmp(denominator);
+        let round_away = match self {
+            Rounding::HalfEven => half == Ordering::Greater || half == Ordering::Equal && quotient.is_odd(),
+            Rounding::HalfUp => half != Ordering::Less,
+            Rounding::Down => false,
+            Rounding::Up => true,
+            Rounding::Ceiling => numerator.is_positive(),
+            Rounding::Floor => numerator.is_negative(),
+        };
+        if round_away {
+            away
+        } else {
+            quotient
+        }
+    }
+}
+
+/// Controls results that cannot be represented exactly, shared by all decimal operations on the current thread.
+///
+/// Multiplication, division and powers are rounded to the precision, addition, subtraction and
+/// remainders are exact and fail with [`NumberError::Overflow`] when the result does not fit.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Context {
+    /// The number of significant digits kept, at most [`Decimal::MAX_SCALE`] digits fit into a decimal.
+    pub precision: u32,
+    pub rounding: Rounding,
+    /// The number of digits a [`BigDecimal`](crate::big_decimal::BigDecimal) may need when written without an exponent.
+    pub max_digits: u64,
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self {
+            precision: Decimal::MAX_SCALE as u32,
+            rounding: Rounding::HalfEven,
+            max_digits: 10_000,
+        }
+    }
+}
+
+thread_local! {
+    static CONTEXT: Cell<Context> = Cell::new(Context::default());
+}
+
+/// The context decimal operations on the current thread use.
+pub fn context() -> Context {
+    CONTEXT.get()
+}
+
+/// Replaces the context decimal operations on the current thread use.
+pub fn set_context(context: Context) {
+    CONTEXT.set(context);
+}
+
 impl Decimal {
+    /// The largest scale whose unit `10^scale` still fits into the `i128` value.
+    pub const MAX_SCALE: u8 = 38;
+
+    /// Creates `value * 10^-scale`, the scale must not exceed [`Decimal::MAX_SCALE`].
     pub fn new(value: i128, scale: u8) -> Self {
         Self { value, scale }
     }
 
+    /// Changes the scale, digits below a smaller scale are truncated.
+    ///
+    /// # Panics
+    ///
+    /// If the value does not fit into the new scale, see [`Decimal::checked_scale`].
     pub fn scale(&mut self, scale: u8) {
-        let div = scale as i16 - self.scale as i16;
-        self.scale = scale;
-        if div == 0 {
-            return;
+        *self = self.checked_scale(scale).expect("attempt to scale decimal with overflow");
+    }
+
+    /// Changes the scale, `None` if the value or the scale would overflow.
+    pub fn checked_scale(self, scale: u8) -> Option<Self> {
+        if scale > Self::MAX_SCALE {
+            return None;
         }
-        if div > 0 {
-            self.value *= 10_i128.pow(div.unsigned_abs() as u32);
-        } else {
-            self.value /= 10_i128.pow(div.unsigned_abs() as u32);
+        let factor = 10_i128.pow(scale.abs_diff(self.scale) as u32);
+        let value = if scale >= self.scale { self.value.checked_mul(factor)? } else { self.value / factor };
+        Some(Decimal::new(value, scale))
+    }
+
+    /// Brings both values to the larger of their scales.
+    fn align(self, other: Self) -> Result<(Self, Self), NumberError> {
+        let scale = self.scale.max(other.scale);
+        match (self.checked_scale(scale), other.checked_scale(scale)) {
+            (Some(a), Some(b)) => Ok((a, b)),
+            _ => Err(NumberError::Overflow),
         }
     }
 
+    /// Removes trailing zeros from the fractional part.
     pub fn pack(&mut self) {
         if self.value == 0 {
-            self.scale(0);
+            self.scale = 0;
             return;
         }
-        let mut i: u8 = 1;
-        while self.value % 10_i128.pow(i as u32) == 0 {
-            i += 1;
+        while self.scale > 0 && self.value % 10 == 0 {
+            self.value /= 10;
+            self.scale -= 1;
+        }
+    }
+
+    /// Applies `operation` to the aligned values and packs the result.
+    fn combine(self, other: Self, operation: fn(i128, i128) -> Option<i128>) -> Result<Self, NumberError> {
+        let (a, b) = self.align(other)?;
+        let mut result = Decimal::new(operation(a.value, b.value).ok_or(NumberError::Overflow)?, a.scale);
+        result.pack();
+        Ok(result)
+    }
+
+    fn unit(&self) -> i128 {
+        10_i128.pow(self.scale as u32)
+    }
+
+    fn compare(&self, other: &Self) -> Ordering {
+        match self.align(*other) {
+            Ok((a, b)) => a.value.cmp(&b.value),
+            // only values far apart in magnitude cannot be aligned
+            Err(_) => {
+                let scale = self.scale.max(other.scale);
+                let widen = |decimal: &Self| BigInt::from(decimal.value) * BigInt::from(10).pow((scale - decimal.scale) as u32);
+                widen(self).cmp(&widen(other))
+            }
+        }
+    }
+
+    /// The value as a fixed point number with at least [`Decimal::MAX_SCALE`] digits, which is exact.
+    fn fixed(self, scale: u32) -> BigInt {
+        BigInt::from(self.value) * BigInt::from(10).pow(scale - self.scale as u32)
+    }
+
+    /// The closest `f64`, see [`ToPrimitive::to_f64`].
+    fn float(self) -> f64 {
+        self.to_string().parse().unwrap_or_default()
+    }
+
+    fn approximate(self, f: impl Fn(f64) -> f64) -> Result<Self, NumberError> {
+        Decimal::from_float(f(self.float()), f64::DIGITS as usize)
+    }
+
+    fn positive(self, operation: &str) -> Result<Self, NumberError> {
+        if self.value <= 0 {
+            return Err(NumberError::Domain(format!("{operation} of non-positive number {self}")));
+        }
+        Ok(self)
+    }
+
+    /// Converts a float rounded to `digits` significant digits, or fewer if the context's precision is lower.
+    fn from_float(value: f64, digits: usize) -> Result<Self, NumberError> {
+        if value.is_nan() {
+            return Err(NumberError::Domain(format!("{value} is not a decimal")));
+        }
+        if value.is_infinite() {
+            return Err(NumberError::Overflow);
+        }
+        let formatted = format!("{value:e}");
+        let (mantissa, exponent) = formatted.split_once('e').unwrap();
+        let fraction = mantissa.split_once('.').map_or(0, |(_, fraction)| fraction.len() as i64);
+        let mantissa = mantissa.replace('.', "").parse::<BigInt>().unwrap();
+        let context = context();
+        let context = Context {
+            precision: context.precision.min(digits as u32),
+            ..context
+        };
+        quotient(&mantissa, &BigInt::one(), exponent.parse::<i64>().unwrap() - fraction, context)
+    }
+
+    /// Raises to an integer power by squaring, exact as long as the result has at most the context's precision.
+    fn powi(self, exponent: BigInt) -> Result<Self, NumberError> {
+        let base = BigInt::from(self.value);
+        if base.is_zero() {
+            return match BigInt::sign(&exponent) {
+                Sign::Minus => Err(NumberError::DivisionByZero),
+                Sign::NoSign => Ok(Decimal::new(1, 0)),
+                Sign::Plus => Ok(Decimal::new(0, 0)),
+            };
+        }
+        let context = context();
+        // a decimal is always below 10^39 and zero below 10^-38, digits beyond twice that are out of range
+        let bound = 2 * (Decimal::MAX_SCALE as i64 + 1);
+        match integer_power(&base, -(self.scale as i64), &exponent, context.precision as usize + GUARD_DIGITS, bound) {
+            Ok((numerator, denominator, exponent)) => quotient(&numerator, &denominator, exponent, context),
+            Err(Power::Huge) => Err(NumberError::Overflow),
+            // beyond the representable range the sign still decides how the result is rounded
+            Err(_) => quotient(&base.signum().pow(exponent.magnitude()), &BigInt::one(), -(Decimal::MAX_SCALE as i64) - 2, context),
+        }
+    }
+
+    /// Integer exponents are exact, see [`Decimal::powi`], fractional exponents are correctly rounded to the
+    /// [`Context`], see [`Decimal::fractional_power`].
+    fn power(self, exponent: Self) -> Result<Self, NumberError> {
+        let mut exponent = exponent;
+        exponent.pack();
+        if exponent.scale == 0 {
+            return self.powi(exponent.value.into());
+        }
+        if self.value < 0 {
+            return Err(NumberError::Domain(format!("{self} cannot be raised to the fractional power {exponent}")));
+        }
+        if self.value == 0 {
+            return self.powi(exponent.value.signum().into());
+        }
+        if let Some((root, power)) = self.exact_root(exponent) {
+            return root.powi(power);
+        }
+        self.fractional_power(exponent)
+    }
+
+    /// `e^(y·ln(x))` of a positive `x`, the logarithm gets as many more digits as the result can have integer
+    /// digits, so its error stays below a unit of the result.
+    fn fractional_power(self, exponent: Self) -> Result<Self, NumberError> {
+        let estimate = exponent.float() * self.float().ln();
+        // like for exp, larger results overflow and smaller ones round like any tiny positive value
+        if estimate > 89.0 {
+            return Err(NumberError::Overflow);
+        }
+        if estimate < -180.0 {
+            return quotient(&BigInt::one(), &BigInt::one(), -(Decimal::MAX_SCALE as i64) - 2, context());
+        }
+        let magnitude = Decimal::MAX_SCALE as u32 + 2;
+        let extra = magnitude + digits(&BigInt::from(exponent.value)) as u32;
+        let ten = |power: u32| BigInt::from(10).pow(power);
+        correctly_rounded(|scale| {
+            let logarithm = transcendental::ln(&self.fixed(scale + extra), scale + extra);
+            let product = logarithm * exponent.value / ten(exponent.scale as u32 + extra - magnitude);
+            transcendental::exp(&product, scale + magnitude) / ten(magnitude)
+        })
+    }
+
+    /// The root is truncated below the digits a decimal holds, see [`root`].
+    fn root(self, degree: u32) -> Result<Self, NumberError> {
+        let exponent = -(Decimal::MAX_SCALE as i64) - 2;
+        quotient(&root(&self.value.into(), self.scale.into(), degree, exponent), &BigInt::from(2), exponent, context())
+    }
+
+    /// The `b`-th root of `self` and `a` if the exponent is `a/b` in lowest terms and the root is a decimal,
+    /// then the power is exact and its approximation might never decide how to round it.
+    fn exact_root(self, exponent: Self) -> Option<(Self, BigInt)> {
+        let (numerator, denominator, power) = exact_root(&self.value.into(), self.scale.into(), &exponent.value.into(), exponent.scale.into())?;
+        let exact = Context {
+            precision: Decimal::MAX_SCALE as u32,
+            ..context()
+        };
+        Some((quotient(&numerator, &denominator, 0, exact).ok()?, power))
+    }
+}
+
+/// Rounds a [`transcendental`] function to the context, see [`transcendental::correctly_rounded`].
+fn correctly_rounded(approximate: impl Fn(u32) -> BigInt) -> Result<Decimal, NumberError> {
+    let context = context();
+    transcendental::correctly_rounded(Decimal::MAX_SCALE as u32, approximate, |approximation, scale| quotient(approximation, &BigInt::one(), -(scale as i64), context))
+}
+
+/// Extra digits kept by intermediate results so the final rounding is not affected by earlier ones.
+pub(crate) const GUARD_DIGITS: usize = 10;
+
+/// The magnitude of a power, values far outside the range of a decimal are not computed.
+pub(crate) enum Power {
+    /// `coefficient * 10^exponent`
+    Finite(BigInt, i64),
+    Huge,
+    Tiny,
+}
+
+pub(crate) fn digits(value: &BigInt) -> usize {
+    value.magnitude().to_string().len()
+}
+
+/// Computes `(base * 10^exponent)^power` for a positive base, truncating intermediate results to `working` digits.
+///
+/// Results whose magnitude exceeds `10^bound` or falls below `10^-bound` are not computed.
+pub(crate) fn power(base: BigInt, exponent: i64, power: num::BigUint, working: usize, bound: i64) -> Power {
+    let truncate = |coefficient: BigInt, exponent: i64| -> Result<(BigInt, i64), Power> {
+        let excess = digits(&coefficient).saturating_sub(working);
+        let (coefficient, exponent) = (coefficient / BigInt::from(10).pow(excess as u32), exponent + excess as i64);
+        match digits(&coefficient) as i64 + exponent {
+            magnitude if magnitude > bound => Err(Power::Huge),
+            magnitude if magnitude < -bound => Err(Power::Tiny),
+            _ => Ok((coefficient, exponent)),
+        }
+    };
+    let run = || -> Result<(BigInt, i64), Power> {
+        let (mut result, mut base, mut power) = ((BigInt::one(), 0), (base, exponent), power);
+        loop {
+            if power.is_odd() {
+                result = truncate(result.0 * &base.0, result.1 + base.1)?;
+            }
+            power >>= 1;
+            if power.is_zero() {
+                return Ok(result);
+            }
+            base = truncate(&base.0 * &base.0, base.1 * 2)?;
         }
-        let this = self.scale - (i - 1);
-        self.scale(this)
+    };
+    match run() {
+        Ok((coefficient, exponent)) => Power::Finite(coefficient, exponent),
+        Err(power) => power,
     }
 }
 
+/// Computes `(base * 10^exponent)^power` for a non-zero base as `numerator / denominator * 10^exponent`, see [`power`].
+///
+/// Fails with [`Power::Huge`] for results above the bound and [`Power::Tiny`] for those below it.
+pub(crate) fn integer_power(base: &BigInt, exponent: i64, power: &BigInt, working: usize, bound: i64) -> Result<(BigInt, BigInt, i64), Power> {
+    let sign = if base.is_negative() && power.is_odd() { -BigInt::one() } else { BigInt::one() };
+    match (self::power(Signed::abs(base), exponent, power.magnitude().clone(), working, bound), power.is_negative()) {
+        (Power::Finite(coefficient, exponent), false) => Ok((coefficient * sign, BigInt::one(), exponent)),
+        (Power::Finite(coefficient, exponent), true) => Ok((sign, coefficient, -exponent)),
+        (Power::Huge, false) | (Power::Tiny, true) => Err(Power::Huge),
+        (Power::Tiny, false) | (Power::Huge, true) => Err(Power::Tiny),
+    }
+}
+
+/// `value * 10^-scale` as a fraction in lowest terms with a positive denominator.
+fn fraction(value: &BigInt, scale: i64) -> (BigInt, BigInt) {
+    let ten = |power: i64| BigInt::from(10).pow(power as u32);
+    let (numerator, denominator) = if scale < 0 { (value * ten(-scale), BigInt::one()) } else { (value.clone(), ten(scale)) };
+    let divisor = numerator.gcd(&denominator);
+    (numerator / &divisor, denominator / divisor)
+}
+
+/// The `b`-th root of a positive `value * 10^-scale` as a fraction and `a`, if the exponent is `a/b` in lowest
+/// terms and the root is rational. Then the power is exact and its approximation might never decide how to round it.
+pub(crate) fn exact_root(value: &BigInt, scale: i64, exponent: &BigInt, exponent_scale: i64) -> Option<(BigInt, BigInt, BigInt)> {
+    let (power, degree) = fraction(exponent, exponent_scale);
+    let (numerator, denominator) = fraction(value, scale);
+    // no integer but one is a power of a degree above its number of bits
+    let bits = numerator.bits().max(denominator.bits());
+    let degree = degree.to_u64().map_or(bits + 1, |degree| degree.min(bits + 1)) as u32;
+    let root = |value: &BigInt| Some(value.nth_root(degree)).filter(|root| &Pow::pow(root, degree) == value);
+    Some((root(&numerator)?, root(&denominator)?, power))
+}
+
+/// The `degree`-th root of `value * 10^-scale` as `(2r + s) * 10^exponent / 2`, where `r` is the root truncated to a
+/// multiple of `10^exponent` and `s` is one if it is inexact. Rounded to fewer digits than `r` has, this rounds
+/// like every value strictly between `r` and the next multiple, so like the exact root.
+pub(crate) fn root(value: &BigInt, scale: i64, degree: u32, exponent: i64) -> BigInt {
+    let ten = |power: i64| BigInt::from(10).pow(power as u32);
+    let shift = -scale - degree as i64 * exponent;
+    let (radicand, remainder) = match shift {
+        0.. => (value.magnitude() * ten(shift).magnitude(), false),
+        _ => {
+            let (radicand, remainder) = value.magnitude().div_rem(ten(-shift).magnitude());
+            (radicand, !remainder.is_zero())
+        }
+    };
+    let root = radicand.nth_root(degree);
+    let inexact = remainder || Pow::pow(&root, degree) != radicand;
+    BigInt::from_biguint(value.sign(), root * 2u8 + inexact as u8)
+}
+
+/// Rounds `numerator / denominator * 10^exponent` for a non-zero numerator to `precision` significant digits,
+/// dropping digits below `10^minimum`. Returns the coefficient and exponent of the result.
+pub(crate) fn round(numerator: &BigInt, denominator: &BigInt, exponent: i64, precision: i64, minimum: Option<i64>, rounding: Rounding) -> (BigInt, i64) {
+    let (numerator, denominator) = if denominator.is_negative() { (-numerator, -denominator) } else { (numerator.clone(), denominator.clone()) };
+    // the integer part of the quotient has `length` digits
+    let ten = |power: i64| BigInt::from(10).pow(power as u32);
+    let difference = digits(&numerator) as i64 - digits(&denominator) as i64;
+    let below = match difference {
+        0.. => Signed::abs(&numerator) < &denominator * ten(difference),
+        _ => Signed::abs(&numerator) * ten(-difference) < denominator,
+    };
+    let length = if below { difference } else { difference + 1 };
+    let shift = (precision - length).min(minimum.map_or(i64::MAX, |minimum| exponent - minimum));
+    let coefficient = match shift {
+        0.. => rounding.divide(&(numerator * ten(shift)), &denominator),
+        _ => rounding.divide(&numerator, &(denominator * ten(-shift))),
+    };
+    (coefficient, exponent - shift)
+}
+
+/// Rounds `numerator / denominator * 10^exponent` to the context's precision.
+fn quotient(numerator: &BigInt, denominator: &BigInt, exponent: i64, context: Context) -> Result<Decimal, NumberError> {
+    if denominator.is_zero() {
+        return Err(NumberError::DivisionByZero);
+    }
+    if numerator.is_zero() {
+        return Ok(Decimal::new(0, 0));
+    }
+    let precision = context.precision.clamp(1, Decimal::MAX_SCALE as u32) as i64;
+    let (coefficient, exponent) = round(numerator, denominator, exponent, precision, Some(-(Decimal::MAX_SCALE as i64)), context.rounding);
+    let value = match exponent {
+        0.. => coefficient * BigInt::from(10).pow(exponent as u32),
+        _ => coefficient,
+    };
+    let mut decimal = Decimal::new(value.to_i128().ok_or(NumberError::Overflow)?, (-exponent).max(0) as u8);
+    decimal.pack();
+    Ok(decimal)
+}
+
 pub fn scale_smallest(a: &mut Decimal, b: &mut Decimal) {
     if a.scale == b.scale {
         return;
@@ -58,12 +453,12 @@ pub fn scale_smallest(a: &mut Decimal, b: &mut Decimal) {
 
 impl Display for Decimal {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let pre = self.value / 10_i128.pow(self.scale as u32);
+        let sign = if self.value < 0 { "-" } else { "" };
+        let (integer, fraction) = (self.value.unsigned_abs() / self.unit() as u128, self.value.unsigned_abs() % self.unit() as u128);
         if self.scale == 0 {
-            write!(f, "{pre}")
+            write!(f, "{sign}{integer}")
         } else {
-            let after = self.value % 10_i128.pow(self.scale as u32);
-            write!(f, "{pre}.{after}")
+            write!(f, "{sign}{integer}.{fraction:0width$}", width = self.scale as usize)
         }
     }
 }
@@ -71,80 +466,485 @@ impl Display for Decimal {
 impl FromStr for Decimal {
     type Err = ParseDecimalError;
 
+    /// A decimal [`Literal`] after an optional sign, like `-1.25` or `1e5`, with at most [`Decimal::MAX_SCALE`]
+    /// fractional digits.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let scale = match s.find('.') {
-            Some(i) => {
-                u8::from_usize(s.len()).ok_or(|| ParseDecimalError {}).map_err(|_| ParseDecimalError {})? - u8::from_usize(i).ok_or(|| ParseDecimalError {}).map_err(|_| ParseDecimalError {})? - 1
-            }
-            None => 0,
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
         };
-
-        let value_str = s.replace('.', "");
-        let value = value_str.parse::<i128>().map_err(|_| ParseDecimalError {})?;
-
-        Ok(Decimal::new(value, scale))
+        let literal = digits.parse::<Literal>().ok().filter(|literal| literal.radix == 10).ok_or(ParseDecimalError {})?;
+        let value = Decimal::from_literal(&literal).ok_or(ParseDecimalError {})?;
+        if negative {
+            return value.try_neg().map_err(|_| ParseDecimalError {});
+        }
+        Ok(value)
     }
 }
 
 impl Add for Decimal {
     type Output = Decimal;
 
-    fn add(mut self, mut rhs: Self) -> Self::Output {
-        scale_smallest(&mut self, &mut rhs);
-        self.value += rhs.value;
-        self.pack();
-        self
+    fn add(self, rhs: Self) -> Self::Output {
+        self.try_add(rhs).expect("attempt to add with overflow")
     }
 }
 
 impl Sub for Decimal {
     type Output = Decimal;
 
-    fn sub(mut self, mut rhs: Self) -> Self::Output {
-        scale_smallest(&mut self, &mut rhs);
-        self.value -= rhs.value;
-        self.pack();
-        self
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.try_sub(rhs).expect("attempt to subtract with overflow")
     }
 }
 
 impl Mul for Decimal {
     type Output = Decimal;
 
-    fn mul(mut self, mut rhs: Self) -> Self::Output {
-        scale_smallest(&mut self, &mut rhs);
-        self.value *= rhs.value;
-        self.pack();
-        self
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.try_mul(rhs).expect("attempt to multiply with overflow")
     }
 }
 
 impl Div for Decimal {
     type Output = Decimal;
 
-    fn div(mut self, mut rhs: Self) -> Self::Output {
-        scale_smallest(&mut self, &mut rhs);
-        self.value /= rhs.value;
-        self.pack();
-        self
+    fn div(self, rhs: Self) -> Self::Output {
+        self.try_div(rhs).expect("attempt to divide by zero or with overflow")
     }
 }
 
 impl Rem for Decimal {
     type Output = Decimal;
 
-    fn rem(mut self, mut rhs: Self) -> Self::Output {
-        scale_smallest(&mut self, &mut rhs);
-        self.value %= rhs.value;
-        self.pack();
-        self
+    fn rem(self, rhs: Self) -> Self::Output {
+        self.try_rem(rhs).expect("attempt to calculate the remainder with a divisor of zero or with overflow")
     }
 }
 
 impl Pow<Decimal> for Decimal {
     type Output = Decimal;
 
-    fn pow(self, _rhs: Decimal) -> Self::Output {
-        todo!()
+    fn pow(self, rhs: Decimal) -> Self::Output {
+        self.power(rhs).expect("attempt to raise decimal to a power with overflow")
+    }
+}
+
+impl Neg for Decimal {
+    type Output = Decimal;
+
+    fn neg(self) -> Self::Output {
+        self.try_neg().expect("attempt to negate with overflow")
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.compare(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+/// Hashes the packed representation, so equal values with different scales have equal hashes.
+impl Hash for Decimal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut packed = *self;
+        packed.pack();
+        packed.value.hash(state);
+        packed.scale.hash(state);
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.compare(other)
+    }
+}
+
+impl Zero for Decimal {
+    fn zero() -> Self {
+        Decimal::new(0, 0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.value == 0
+    }
+}
+
+impl One for Decimal {
+    fn one() -> Self {
+        Decimal::new(1, 0)
+    }
+}
+
+/// Radixes other than 10 only accept integers.
+impl Num for Decimal {
+    type FromStrRadixErr = ParseDecimalError;
+
+    fn from_str_radix(text: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        match radix {
+            10 => text.parse(),
+            _ => i128::from_str_radix(text, radix).map(|value| Decimal::new(value, 0)).map_err(|_| ParseDecimalError {}),
+        }
+    }
+}
+
+/// Like the primitive signed integers, [`Signed::abs`] panics if the absolute value overflows.
+impl Signed for Decimal {
+    fn abs(&self) -> Self {
+        if self.value < 0 {
+            -*self
+        } else {
+            *self
+        }
+    }
+
+    fn abs_sub(&self, other: &Self) -> Self {
+        if self <= other {
+            Decimal::zero()
+        } else {
+            *self - *other
+        }
+    }
+
+    fn signum(&self) -> Self {
+        Decimal::new(self.value.signum(), 0)
+    }
+
+    fn is_positive(&self) -> bool {
+        self.value > 0
+    }
+
+    fn is_negative(&self) -> bool {
+        self.value < 0
+    }
+}
+
+/// The integers of the range of the `i128` value.
+impl Bounded for Decimal {
+    fn min_value() -> Self {
+        Decimal::new(i128::MIN, 0)
+    }
+
+    fn max_value() -> Self {
+        Decimal::new(i128::MAX, 0)
+    }
+}
+
+macro_rules! checked {
+    ($($trait:ident: $name:ident => $operation:ident),*) => {$(
+        impl $trait for Decimal {
+            fn $name(&self, rhs: &Self) -> Option<Self> {
+                self.$operation(*rhs).ok()
+            }
+        }
+    )*};
+}
+
+checked!(CheckedAdd: checked_add => try_add, CheckedSub: checked_sub => try_sub, CheckedMul: checked_mul => try_mul, CheckedDiv: checked_div => try_div, CheckedRem: checked_rem => try_rem);
+
+impl CheckedNeg for Decimal {
+    fn checked_neg(&self) -> Option<Self> {
+        self.try_neg().ok()
+    }
+}
+
+/// Floats are converted through their shortest representation rounded to the [`Context`]'s precision,
+/// so `0.1_f64` becomes `0.1`.
+impl FromPrimitive for Decimal {
+    fn from_i64(n: i64) -> Option<Self> {
+        Some(n.into())
+    }
+
+    fn from_u64(n: u64) -> Option<Self> {
+        Some(n.into())
+    }
+
+    fn from_i128(n: i128) -> Option<Self> {
+        Some(n.into())
+    }
+
+    fn from_u128(n: u128) -> Option<Self> {
+        n.try_into().ok().map(|n| Decimal::new(n, 0))
+    }
+
+    fn from_f32(n: f32) -> Option<Self> {
+        Decimal::from_f64(n.to_string().parse().ok()?)
+    }
+
+    fn from_f64(n: f64) -> Option<Self> {
+        Decimal::from_float(n, usize::MAX).ok()
+    }
+}
+
+/// Integer conversions truncate towards zero.
+impl ToPrimitive for Decimal {
+    fn to_i64(&self) -> Option<i64> {
+        self.to_i128()?.try_into().ok()
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        self.to_i128()?.try_into().ok()
+    }
+
+    fn to_i128(&self) -> Option<i128> {
+        Some(self.value / self.unit())
+    }
+
+    fn to_u128(&self) -> Option<u128> {
+        self.to_i128()?.try_into().ok()
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        Some(self.float())
+    }
+}
+
+impl NumCast for Decimal {
+    fn from<T: ToPrimitive>(n: T) -> Option<Self> {
+        match n.to_i128() {
+            Some(integer) if n.to_f64().is_none_or(|float| float == integer as f64) => Some(integer.into()),
+            _ => Decimal::from_f64(n.to_f64()?),
+        }
+    }
+}
+
+macro_rules! from_integer {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Decimal {
+            fn from(value: $t) -> Self {
+                Decimal::new(value.into(), 0)
+            }
+        }
+    )*};
+}
+
+from_integer!(i8, i16, i32, i64, i128, u8, u16, u32, u64);
+
+/// Fails like [`FromPrimitive::from_f64`] for `NaN`, infinite values and values out of range.
+impl TryFrom<f64> for Decimal {
+    type Error = NumberError;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        Decimal::from_float(value, usize::MAX)
+    }
+}
+
+impl From<Decimal> for f64 {
+    fn from(value: Decimal) -> Self {
+        value.float()
+    }
+}
+
+impl Number for Decimal {
+    fn from_literal(literal: &Literal) -> Option<Self> {
+        if literal.radix != 10 {
+            return Some(Decimal::new(literal.to_integer()?.try_into().ok()?, 0));
+        }
+        let (digits, exponent) = literal.to_digits();
+        let value = digits.parse::<i128>().ok()?;
+        match u32::try_from(exponent) {
+            Ok(exponent) => Some(Decimal::new(value.checked_mul(10_i128.checked_pow(exponent)?)?, 0)),
+            Err(_) => Some(Decimal::new(value, u8::try_from(-exponent).ok().filter(|scale| *scale <= Self::MAX_SCALE)?)),
+        }
+    }
+
+    fn try_add(self, rhs: Self) -> Result<Self, NumberError> {
+        self.combine(rhs, i128::checked_add)
+    }
+
+    fn try_sub(self, rhs: Self) -> Result<Self, NumberError> {
+        self.combine(rhs, i128::checked_sub)
+    }
+
+    fn try_mul(self, rhs: Self) -> Result<Self, NumberError> {
+        let product = BigInt::from(self.value) * rhs.value;
+        quotient(&product, &BigInt::one(), -(self.scale as i64 + rhs.scale as i64), context())
+    }
+
+    fn try_div(self, rhs: Self) -> Result<Self, NumberError> {
+        quotient(&self.value.into(), &rhs.value.into(), rhs.scale as i64 - self.scale as i64, context())
+    }
+
+    fn try_rem(self, rhs: Self) -> Result<Self, NumberError> {
+        if rhs.value == 0 {
+            return Err(NumberError::DivisionByZero);
+        }
+        self.combine(rhs, i128::checked_rem)
+    }
+
+    fn try_pow(self, rhs: Self) -> Result<Self, NumberError> {
+        self.power(rhs)
+    }
+
+    fn try_neg(self) -> Result<Self, NumberError> {
+        Ok(Decimal::new(self.value.checked_neg().ok_or(NumberError::Overflow)?, self.scale))
+    }
+
+    fn to_float(&self) -> Option<f64> {
+        ToPrimitive::to_f64(self)
+    }
+
+    fn from_bool(value: bool) -> Self {
+        Decimal::new(value.into(), 0)
+    }
+
+    fn try_cmp(&self, other: &Self) -> Result<Option<Ordering>, NumberError> {
+        Ok(Some(self.compare(other)))
+    }
+}
+
+/// Rounding and sign functions are exact. Roots, `exp`, `ln`, `sin`, `cos`, `atan` and the constants are
+/// correctly rounded to the [`Context`], the other transcendental functions are approximated through `f64`
+/// and rounded to its precision of 15 significant digits, or the context's if that is lower.
+impl Elementary for Decimal {
+    fn sqrt(self) -> Result<Self, NumberError> {
+        if self.value < 0 {
+            return Err(NumberError::Domain(format!("square root of negative number {self}")));
+        }
+        self.root(2)
+    }
+
+    fn cbrt(self) -> Result<Self, NumberError> {
+        self.root(3)
+    }
+
+    fn exp(self) -> Result<Self, NumberError> {
+        if self.is_zero() {
+            return Ok(Decimal::one());
+        }
+        // e^89 exceeds the largest decimal, below e^-180 every result rounds like any tiny positive value
+        if self > Decimal::new(89, 0) {
+            return Err(NumberError::Overflow);
+        }
+        if self < Decimal::new(-180, 0) {
+            return quotient(&BigInt::one(), &BigInt::one(), -(Decimal::MAX_SCALE as i64) - 2, context());
+        }
+        correctly_rounded(|scale| transcendental::exp(&self.fixed(scale), scale))
+    }
+
+    fn ln(self) -> Result<Self, NumberError> {
+        if self.positive("logarithm")?.is_one() {
+            return Ok(Decimal::zero());
+        }
+        correctly_rounded(|scale| transcendental::ln(&self.fixed(scale), scale))
+    }
+
+    fn log10(self) -> Result<Self, NumberError> {
+        self.positive("logarithm")?.approximate(f64::log10)
+    }
+
+    fn log2(self) -> Result<Self, NumberError> {
+        self.positive("logarithm")?.approximate(f64::log2)
+    }
+
+    fn log(self, base: Self) -> Result<Self, NumberError> {
+        self.positive("logarithm")?.approximate(|v| v.log(base.float()))
+    }
+
+    fn sin(self) -> Result<Self, NumberError> {
+        if self.is_zero() {
+            return Ok(Decimal::zero());
+        }
+        correctly_rounded(|scale| transcendental::sine(&self.fixed(scale), scale, false))
+    }
+
+    fn cos(self) -> Result<Self, NumberError> {
+        if self.is_zero() {
+            return Ok(Decimal::one());
+        }
+        correctly_rounded(|scale| transcendental::sine(&self.fixed(scale), scale, true))
+    }
+
+    fn tan(self) -> Result<Self, NumberError> {
+        self.approximate(f64::tan)
+    }
+
+    fn asin(self) -> Result<Self, NumberError> {
+        self.approximate(f64::asin)
+    }
+
+    fn acos(self) -> Result<Self, NumberError> {
+        self.approximate(f64::acos)
+    }
+
+    fn atan(self) -> Result<Self, NumberError> {
+        if self.is_zero() {
+            return Ok(Decimal::zero());
+        }
+        correctly_rounded(|scale| transcendental::atan(&self.fixed(scale), scale))
+    }
+
+    fn sinh(self) -> Result<Self, NumberError> {
+        self.approximate(f64::sinh)
+    }
+
+    fn cosh(self) -> Result<Self, NumberError> {
+        self.approximate(f64::cosh)
+    }
+
+    fn tanh(self) -> Result<Self, NumberError> {
+        self.approximate(f64::tanh)
+    }
+
+    fn asinh(self) -> Result<Self, NumberError> {
+        self.approximate(f64::asinh)
+    }
+
+    fn acosh(self) -> Result<Self, NumberError> {
+        self.approximate(f64::acosh)
+    }
+
+    fn atanh(self) -> Result<Self, NumberError> {
+        self.approximate(f64::atanh)
+    }
+
+    fn pi() -> Result<Self, NumberError> {
+        correctly_rounded(transcendental::pi)
+    }
+
+    fn e() -> Result<Self, NumberError> {
+        correctly_rounded(|scale| transcendental::exp(&BigInt::from(10).pow(scale), scale))
+    }
+
+    fn abs(self) -> Result<Self, NumberError> {
+        Ok(Decimal::new(self.value.checked_abs().ok_or(NumberError::Overflow)?, self.scale))
+    }
+
+    fn sign(self) -> Result<Self, NumberError> {
+        Ok(Decimal::new(self.value.signum(), 0))
+    }
+
+    fn minimum(self, other: Self) -> Result<Self, NumberError> {
+        Ok(if self.compare(&other) == Ordering::Greater { other } else { self })
+    }
+
+    fn maximum(self, other: Self) -> Result<Self, NumberError> {
+        Ok(if self.compare(&other) == Ordering::Less { other } else { self })
+    }
+
+    fn floor(self) -> Result<Self, NumberError> {
+        Ok(Decimal::new(self.value.div_euclid(self.unit()), 0))
+    }
+
+    fn ceil(self) -> Result<Self, NumberError> {
+        let quotient = self.value.div_euclid(self.unit());
+        Ok(Decimal::new(if self.value.rem_euclid(self.unit()) == 0 { quotient } else { quotient + 1 }, 0))
+    }
+
+    fn round(self) -> Result<Self, NumberError> {
+        let half = self.unit() / 2 * self.value.signum();
+        Ok(Decimal::new(self.value.checked_add(half).ok_or(NumberError::Overflow)? / self.unit(), 0))
+    }
+
+    fn trunc(self) -> Result<Self, NumberError> {
+        Ok(Decimal::new(self.value / self.unit(), 0))
     }
 }
@@ -0,0 +1,126 @@
+use crate::builtins::Chain;
+use crate::number::{Number, NumberError};
+use crate::parse::Literal;
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+
+/// A dual number `value + derivative·ε` with `ε² = 0`, evaluating an expression with them is forward mode
+/// automatic differentiation.
+///
+/// The variable differentiated by is [`Dual::variable`], everything else is a [`Dual::constant`]. Every
+/// operation applies its exact derivative, so the result is the derivative up to the rounding of `f64`
+/// rather than a difference quotient, see [`derivative_at`](crate::derivative_at). Values are ordered
+/// by their value, functions without a derivative like `floor` are unsupported.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Dual {
+    value: f64,
+    derivative: f64,
+}
+
+impl Dual {
+    pub fn new(value: f64, derivative: f64) -> Self {
+        Self { value, derivative }
+    }
+
+    /// A value that does not depend on the variable.
+    pub fn constant(value: f64) -> Self {
+        Self::new(value, 0.0)
+    }
+
+    /// The variable differentiated by, at the given value.
+    pub fn variable(value: f64) -> Self {
+        Self::new(value, 1.0)
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn derivative(&self) -> f64 {
+        self.derivative
+    }
+}
+
+impl Display for Dual {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} + {}ε", self.value, self.derivative)
+    }
+}
+
+impl Chain for Dual {
+    fn value(&self) -> f64 {
+        self.value
+    }
+
+    fn constant(value: f64) -> Self {
+        Dual::constant(value)
+    }
+
+    /// A constant stays constant, even where the derivative is infinite like for `sqrt(0)`.
+    fn chain(&self, value: f64, derivative: f64) -> Self {
+        Self::new(value, if self.derivative == 0.0 { 0.0 } else { derivative * self.derivative })
+    }
+}
+
+impl Number for Dual {
+    fn from_literal(literal: &Literal) -> Option<Self> {
+        f64::from_literal(literal).map(Self::constant)
+    }
+
+    fn try_add(self, rhs: Self) -> Result<Self, NumberError> {
+        Ok(Self::new(self.value + rhs.value, self.derivative + rhs.derivative))
+    }
+
+    fn try_sub(self, rhs: Self) -> Result<Self, NumberError> {
+        Ok(Self::new(self.value - rhs.value, self.derivative - rhs.derivative))
+    }
+
+    fn try_mul(self, rhs: Self) -> Result<Self, NumberError> {
+        Ok(Self::new(self.value * rhs.value, self.derivative * rhs.value + self.value * rhs.derivative))
+    }
+
+    fn try_div(self, rhs: Self) -> Result<Self, NumberError> {
+        if rhs.value == 0.0 {
+            return Err(NumberError::DivisionByZero);
+        }
+        let quotient = self.value / rhs.value;
+        Ok(Self::new(quotient, (self.derivative - quotient * rhs.derivative) / rhs.value))
+    }
+
+    fn try_rem(self, rhs: Self) -> Result<Self, NumberError> {
+        if rhs.value == 0.0 {
+            return Err(NumberError::DivisionByZero);
+        }
+        let quotient = (self.value / rhs.value).trunc();
+        Ok(Self::new(self.value % rhs.value, self.derivative - quotient * rhs.derivative))
+    }
+
+    /// `(u^v)' = v·u^(v-1)·u' + ln(u)·u^v·v'`, the second term needs a positive base unless `v` is constant.
+    fn try_pow(self, rhs: Self) -> Result<Self, NumberError> {
+        let power = self.value.powf(rhs.value);
+        let base = if self.derivative == 0.0 {
+            0.0
+        } else {
+            rhs.value * self.value.powf(rhs.value - 1.0) * self.derivative
+        };
+        if rhs.derivative == 0.0 {
+            return Ok(Self::new(power, base));
+        }
+        if self.value <= 0.0 {
+            return Err(NumberError::Domain(format!("{} cannot be raised to a power depending on the variable, it is not positive", self.value)));
+        }
+        Ok(Self::new(power, base + self.value.ln() * power * rhs.derivative))
+    }
+
+    fn try_neg(self) -> Result<Self, NumberError> {
+        Ok(Self::new(-self.value, -self.derivative))
+    }
+
+    fn from_bool(value: bool) -> Self {
+        Self::constant(u8::from(value).into())
+    }
+
+    fn try_cmp(&self, other: &Self) -> Result<Option<Ordering>, NumberError> {
+        Ok(self.value.partial_cmp(&other.value))
+    }
+}
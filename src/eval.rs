@@ -1,35 +1,115 @@
-use crate::eval::EvalError::VariableNotFound;
-use crate::parse::Expr;
-use num::traits::Pow;
+use crate::eval::EvalError::{ArityMismatch, DivisionByZero, Domain, FunctionNotFound, Overflow, Unsupported, VariableNotFound};
+use crate::function::{Arity, Functions};
+use crate::number::{Number, NumberError};
+use crate::parse::{Expr, ExprKind, Span};
+use std::any::type_name;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fmt::{Debug, Display, Formatter};
-use std::ops::{Add, Div, Mul, Rem, Sub};
 
 #[derive(Debug, PartialEq)]
 pub enum EvalError {
-    VariableNotFound(String),
+    VariableNotFound(String, Span),
+    Unsupported(&'static str, String, Span),
+    Domain(String, Span),
+    FunctionNotFound(String, Span),
+    ArityMismatch(String, Arity, usize, Span),
+    Overflow(Span),
+    DivisionByZero(Span),
+}
+
+impl EvalError {
+    pub fn span(&self) -> Span {
+        match self {
+            VariableNotFound(_, span) | Unsupported(_, _, span) | Domain(_, span) | FunctionNotFound(_, span) | ArityMismatch(_, _, _, span) | Overflow(span) | DivisionByZero(span) => *span,
+        }
+    }
+
+    fn from_number<T>(error: NumberError, span: Span) -> Self {
+        match error {
+            NumberError::Unsupported(operation) => Unsupported(operation, type_name::<T>().into(), span),
+            NumberError::Domain(message) => Domain(message, span),
+            NumberError::Overflow => Overflow(span),
+            NumberError::DivisionByZero => DivisionByZero(span),
+        }
+    }
 }
 
 impl Display for EvalError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            VariableNotFound(v) => write!(f, "variable {v} was not found"),
+            VariableNotFound(v, _) => write!(f, "variable {v} was not found"),
+            Unsupported(operation, type_name, _) => write!(f, "{operation} is not supported for {type_name}"),
+            Domain(message, _) => write!(f, "{message}"),
+            FunctionNotFound(name, _) => write!(f, "function {name} was not found"),
+            ArityMismatch(name, arity, count, _) => write!(f, "function {name} expects {arity} but got {count}"),
+            Overflow(_) => write!(f, "arithmetic overflow"),
+            DivisionByZero(_) => write!(f, "division by zero"),
         }
     }
 }
 
-pub fn eval_expr<T>(expr: &Expr<T>, variables: &HashMap<String, T>) -> Result<T, EvalError>
+/// Evaluates the expression, `&&`, `||` and `? :` only evaluate the operands that decide their result.
+pub fn eval_expr<T>(expr: &Expr<T>, variables: &HashMap<String, T>, functions: &Functions<T>) -> Result<T, EvalError>
 where
-    T: Pow<T, Output = T> + Copy + Debug + Sized + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T> + Rem<Output = T>,
+    T: Number + Clone + Debug,
 {
-    Ok(match expr {
-        Expr::Value(v) => *v,
-        Expr::Variable(v) => *variables.get(v).ok_or_else(|| VariableNotFound(v.into()))?,
-        Expr::Add(a, b) => eval_expr(a.as_ref(), variables)? + eval_expr(b.as_ref(), variables)?,
-        Expr::Sub(a, b) => eval_expr(a.as_ref(), variables)? - eval_expr(b.as_ref(), variables)?,
-        Expr::Mul(a, b) => eval_expr(a.as_ref(), variables)? * eval_expr(b.as_ref(), variables)?,
-        Expr::Div(a, b) => eval_expr(a.as_ref(), variables)? / eval_expr(b.as_ref(), variables)?,
-        Expr::Pow(a, b) => eval_expr(a.as_ref(), variables)?.pow(eval_expr(b.as_ref(), variables)?),
-        Expr::Rem(a, b) => eval_expr(a.as_ref(), variables)? % eval_expr(b.as_ref(), variables)?,
+    let eval = |e: &Expr<T>| eval_expr(e, variables, functions);
+    let number = |e: NumberError| EvalError::from_number::<T>(e, expr.span);
+    let truthy = |e: &Expr<T>| eval(e)?.is_truthy().map_err(number);
+    let binary = |a: &Expr<T>, b: &Expr<T>, operation: fn(T, T) -> Result<T, NumberError>| operation(eval(a)?, eval(b)?).map_err(number);
+    let compare = |a: &Expr<T>, b: &Expr<T>, accept: fn(Ordering) -> bool| -> Result<T, EvalError> {
+        let ordering = eval(a)?.try_cmp(&eval(b)?).map_err(number)?;
+        Ok(T::from_bool(ordering.is_some_and(accept)))
+    };
+    Ok(match &expr.kind {
+        ExprKind::Value(v) => v.clone(),
+        ExprKind::Variable(v) => match variables.get(v) {
+            Some(value) => value.clone(),
+            // constants are functions without arguments, variables of the same name shadow them
+            None => match functions.get(v).filter(|function| function.arity.accepts(0)) {
+                Some(function) => function.call(&[]).map_err(number)?,
+                None => return Err(VariableNotFound(v.into(), expr.span)),
+            },
+        },
+        ExprKind::Add(a, b) => binary(a, b, T::try_add)?,
+        ExprKind::Sub(a, b) => binary(a, b, T::try_sub)?,
+        ExprKind::Mul(a, b) => binary(a, b, T::try_mul)?,
+        ExprKind::Div(a, b) => binary(a, b, T::try_div)?,
+        ExprKind::Pow(a, b) => binary(a, b, T::try_pow)?,
+        ExprKind::Rem(a, b) => binary(a, b, T::try_rem)?,
+        ExprKind::Call(name, args) => {
+            let function = functions.get(name).ok_or_else(|| FunctionNotFound(name.clone(), expr.span))?;
+            if !function.arity.accepts(args.len()) {
+                return Err(ArityMismatch(name.clone(), function.arity, args.len(), expr.span));
+            }
+            let args = args.iter().map(eval).collect::<Result<Vec<T>, EvalError>>()?;
+            function.call(&args).map_err(number)?
+        }
+        ExprKind::Neg(a) => eval(a)?.try_neg().map_err(number)?,
+        ExprKind::Eq(a, b) => T::from_bool(eval(a)?.try_eq(&eval(b)?).map_err(number)?),
+        ExprKind::Ne(a, b) => T::from_bool(!eval(a)?.try_eq(&eval(b)?).map_err(number)?),
+        ExprKind::Lt(a, b) => compare(a, b, Ordering::is_lt)?,
+        ExprKind::Le(a, b) => compare(a, b, Ordering::is_le)?,
+        ExprKind::Gt(a, b) => compare(a, b, Ordering::is_gt)?,
+        ExprKind::Ge(a, b) => compare(a, b, Ordering::is_ge)?,
+        ExprKind::And(a, b) => T::from_bool(truthy(a)? && truthy(b)?),
+        ExprKind::Or(a, b) => T::from_bool(truthy(a)? || truthy(b)?),
+        ExprKind::Not(a) => T::from_bool(!truthy(a)?),
+        ExprKind::BitAnd(a, b) => binary(a, b, T::try_bitand)?,
+        ExprKind::BitOr(a, b) => binary(a, b, T::try_bitor)?,
+        ExprKind::BitXor(a, b) => binary(a, b, T::try_bitxor)?,
+        ExprKind::BitNot(a) => eval(a)?.try_bitnot().map_err(number)?,
+        ExprKind::Shl(a, b) => binary(a, b, T::try_shl)?,
+        ExprKind::Shr(a, b) => binary(a, b, T::try_shr)?,
+        ExprKind::Interval(lo, hi) => binary(lo, hi, T::try_interval)?,
+        ExprKind::Uncertain(value, uncertainty) => binary(value, uncertainty, T::try_uncertain)?,
+        ExprKind::Conditional(condition, then, otherwise) => {
+            if truthy(condition)? {
+                eval(then)?
+            } else {
+                eval(otherwise)?
+            }
+        }
     })
 }
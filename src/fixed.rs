@@ -0,0 +1,294 @@
+use crate::builtins::Elementary;
+use crate::decimal::{context, power, Power, Rounding};
+use crate::number::{Number, NumberError};
+use crate::parse::Literal;
+use crate::transcendental;
+use num::integer::Roots;
+use num::{BigInt, One, Signed, ToPrimitive, Zero};
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+
+/// A fixed point number `value * 10^-SCALE`, for example `Fixed<4>` for basis points.
+///
+/// Unlike [`Decimal`](crate::decimal::Decimal) the scale is part of the type, so every result has exactly `SCALE`
+/// fractional digits. Multiplication, division, powers and literals with more digits are rounded with the
+/// [`Context`](crate::decimal::Context)'s rounding, addition, subtraction and remainders are exact.
+/// Results that do not fit into the `i128` value fail with [`NumberError::Overflow`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Fixed<const SCALE: u8> {
+    value: i128,
+}
+
+impl<const SCALE: u8> Fixed<SCALE> {
+    /// `10^SCALE`, the value of one.
+    pub const UNIT: i128 = {
+        assert!(SCALE <= 38, "the scale of a fixed point number is at most 38");
+        10_i128.pow(SCALE as u32)
+    };
+
+    /// Creates the number `value * 10^-SCALE`.
+    pub fn from_raw(value: i128) -> Self {
+        Self { value }
+    }
+
+    /// The value in units of `10^-SCALE`.
+    pub fn raw(self) -> i128 {
+        self.value
+    }
+
+    fn unit() -> BigInt {
+        BigInt::from(Self::UNIT)
+    }
+
+    /// Rounds `numerator / denominator` units with the context's rounding.
+    fn rounded(numerator: &BigInt, denominator: &BigInt) -> Result<Self, NumberError> {
+        let (numerator, denominator) = if denominator.is_negative() {
+            (-numerator, -denominator)
+        } else {
+            (numerator.clone(), denominator.clone())
+        };
+        let value = context().rounding.divide(&numerator, &denominator);
+        Ok(Self::from_raw(value.to_i128().ok_or(NumberError::Overflow)?))
+    }
+
+    /// The value as a fixed point number with more digits, which is exact.
+    fn fixed(self, scale: u32) -> BigInt {
+        BigInt::from(self.value) * BigInt::from(10).pow(scale - SCALE as u32)
+    }
+
+    /// Rounds a [`transcendental`] function to the scale, see [`transcendental::correctly_rounded`].
+    fn correctly_rounded(approximate: impl Fn(u32) -> BigInt) -> Result<Self, NumberError> {
+        transcendental::correctly_rounded(SCALE as u32, approximate, |approximation, scale| Self::rounded(approximation, &BigInt::from(10).pow(scale - SCALE as u32)))
+    }
+
+    /// A positive value too small to compute, rounded like any value below a hundredth of a unit.
+    fn tiny(negative: bool) -> Result<Self, NumberError> {
+        let sign = if negative { -BigInt::one() } else { BigInt::one() };
+        Self::rounded(&sign, &BigInt::from(100))
+    }
+
+    /// Raises to an integer power, the power keeps enough digits that only values extremely close to a rounding
+    /// boundary could be rounded differently than their exact value.
+    fn powi(self, exponent: i128) -> Result<Self, NumberError> {
+        if self.value == 0 {
+            return match exponent.signum() {
+                -1 => Err(NumberError::DivisionByZero),
+                0 => Ok(Self::from_raw(Self::UNIT)),
+                _ => Ok(self),
+            };
+        }
+        let negative = self.value < 0 && exponent % 2 != 0;
+        let sign = if negative { -BigInt::one() } else { BigInt::one() };
+        // an i128 is below 10^39 and a unit is 10^-SCALE, digits beyond twice that are out of range
+        let bound = 2 * (39 + SCALE as i64);
+        let working = 2 * bound as usize;
+        let magnitude = BigInt::from(self.value.unsigned_abs());
+        let ten = |power: i64| BigInt::from(10).pow(power as u32);
+        match (power(magnitude, -(SCALE as i64), exponent.unsigned_abs().into(), working, bound), exponent < 0) {
+            // coefficient * 10^exponent with the unit at 10^-SCALE
+            (Power::Finite(coefficient, exponent), false) => match exponent + SCALE as i64 {
+                shift @ 0.. => Self::rounded(&(coefficient * sign * ten(shift)), &BigInt::one()),
+                shift => Self::rounded(&(coefficient * sign), &ten(-shift)),
+            },
+            (Power::Finite(coefficient, exponent), true) => match SCALE as i64 - exponent {
+                shift @ 0.. => Self::rounded(&(sign * ten(shift)), &coefficient),
+                shift => Self::rounded(&sign, &(coefficient * ten(-shift))),
+            },
+            (Power::Huge, false) | (Power::Tiny, true) => Err(NumberError::Overflow),
+            (Power::Tiny, false) | (Power::Huge, true) => Self::tiny(negative),
+        }
+    }
+
+    /// Rounds to an integer, the result keeps the scale.
+    fn integer(self, rounding: Rounding) -> Result<Self, NumberError> {
+        let integer = rounding.divide(&self.value.into(), &Self::unit());
+        (integer * Self::UNIT).to_i128().map(Self::from_raw).ok_or(NumberError::Overflow)
+    }
+}
+
+impl<const SCALE: u8> Display for Fixed<SCALE> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.value < 0 { "-" } else { "" };
+        let unit = Self::UNIT as u128;
+        let (integer, fraction) = (self.value.unsigned_abs() / unit, self.value.unsigned_abs() % unit);
+        if SCALE == 0 {
+            write!(f, "{sign}{integer}")
+        } else {
+            write!(f, "{sign}{integer}.{fraction:0width$}", width = SCALE as usize)
+        }
+    }
+}
+
+impl<const SCALE: u8> Number for Fixed<SCALE> {
+    fn from_literal(literal: &Literal) -> Option<Self> {
+        if literal.radix != 10 {
+            return Some(Self::from_raw(i128::try_from(literal.to_integer()?).ok()?.checked_mul(Self::UNIT)?));
+        }
+        let (digits, exponent) = literal.to_digits();
+        let digits = digits.parse::<BigInt>().ok()?;
+        if digits.is_zero() {
+            return Some(Self::default());
+        }
+        // the exponent of the last digit relative to the unit
+        let shift = exponent + SCALE as i64;
+        let ten = |power: i64| BigInt::from(10).pow(power as u32);
+        match shift {
+            0.. if shift <= 39 => Self::rounded(&(digits * ten(shift)), &BigInt::one()).ok(),
+            0.. => None,
+            _ if digits.bits() as f64 * std::f64::consts::LOG10_2 < (-shift - 2) as f64 => Self::tiny(false).ok(),
+            _ => Self::rounded(&digits, &ten(-shift)).ok(),
+        }
+    }
+
+    fn try_add(self, rhs: Self) -> Result<Self, NumberError> {
+        self.value.checked_add(rhs.value).map(Self::from_raw).ok_or(NumberError::Overflow)
+    }
+
+    fn try_sub(self, rhs: Self) -> Result<Self, NumberError> {
+        self.value.checked_sub(rhs.value).map(Self::from_raw).ok_or(NumberError::Overflow)
+    }
+
+    fn try_mul(self, rhs: Self) -> Result<Self, NumberError> {
+        Self::rounded(&(BigInt::from(self.value) * rhs.value), &Self::unit())
+    }
+
+    fn try_div(self, rhs: Self) -> Result<Self, NumberError> {
+        if rhs.value == 0 {
+            return Err(NumberError::DivisionByZero);
+        }
+        Self::rounded(&(BigInt::from(self.value) * Self::UNIT), &rhs.value.into())
+    }
+
+    fn try_rem(self, rhs: Self) -> Result<Self, NumberError> {
+        if rhs.value == 0 {
+            return Err(NumberError::DivisionByZero);
+        }
+        Ok(Self::from_raw(self.value.checked_rem(rhs.value).unwrap_or(0)))
+    }
+
+    /// Only integer exponents are supported, fractional powers are rarely representable with a fixed scale.
+    fn try_pow(self, rhs: Self) -> Result<Self, NumberError> {
+        if rhs.value % Self::UNIT != 0 {
+            return Err(NumberError::Domain(format!("{self} cannot be raised to the fractional power {rhs}")));
+        }
+        self.powi(rhs.value / Self::UNIT)
+    }
+
+    fn try_neg(self) -> Result<Self, NumberError> {
+        self.value.checked_neg().map(Self::from_raw).ok_or(NumberError::Overflow)
+    }
+
+    fn to_float(&self) -> Option<f64> {
+        Some(self.value as f64 / Self::UNIT as f64)
+    }
+
+    fn from_bool(value: bool) -> Self {
+        Self::from_raw(if value { Self::UNIT } else { 0 })
+    }
+
+    fn try_cmp(&self, other: &Self) -> Result<Option<Ordering>, NumberError> {
+        Ok(Some(self.cmp(other)))
+    }
+}
+
+/// Rounding and sign functions are exact, `sqrt`, `exp`, `ln`, `sin`, `cos`, `atan` and the constants are
+/// correctly rounded to the scale, the other functions are not supported.
+impl<const SCALE: u8> Elementary for Fixed<SCALE> {
+    /// Rounded like [`Decimal::sqrt`](crate::decimal::Decimal), through the root truncated to one more digit.
+    fn sqrt(self) -> Result<Self, NumberError> {
+        if self.value < 0 {
+            return Err(NumberError::Domain(format!("square root of negative number {self}")));
+        }
+        let scale = SCALE as u32 + 1;
+        let radicand = self.fixed(2 * scale);
+        let root = Roots::sqrt(&radicand);
+        let remainder = BigInt::from(&root * &root != radicand);
+        Self::rounded(&(root * 2 + remainder), &BigInt::from(20))
+    }
+
+    fn exp(self) -> Result<Self, NumberError> {
+        if self.value == 0 {
+            return Ok(Self::from_raw(Self::UNIT));
+        }
+        // e^89 exceeds every fixed point number, below e^-180 every result rounds like any tiny positive value
+        if self.value > Self::UNIT.saturating_mul(89) {
+            return Err(NumberError::Overflow);
+        }
+        if self.value < Self::UNIT.saturating_mul(-180) {
+            return Self::tiny(false);
+        }
+        Self::correctly_rounded(|scale| transcendental::exp(&self.fixed(scale), scale))
+    }
+
+    fn ln(self) -> Result<Self, NumberError> {
+        if self.value <= 0 {
+            return Err(NumberError::Domain(format!("logarithm of non-positive number {self}")));
+        }
+        if self.value == Self::UNIT {
+            return Ok(Self::default());
+        }
+        Self::correctly_rounded(|scale| transcendental::ln(&self.fixed(scale), scale))
+    }
+
+    fn sin(self) -> Result<Self, NumberError> {
+        if self.value == 0 {
+            return Ok(self);
+        }
+        Self::correctly_rounded(|scale| transcendental::sine(&self.fixed(scale), scale, false))
+    }
+
+    fn cos(self) -> Result<Self, NumberError> {
+        if self.value == 0 {
+            return Ok(Self::from_raw(Self::UNIT));
+        }
+        Self::correctly_rounded(|scale| transcendental::sine(&self.fixed(scale), scale, true))
+    }
+
+    fn atan(self) -> Result<Self, NumberError> {
+        if self.value == 0 {
+            return Ok(self);
+        }
+        Self::correctly_rounded(|scale| transcendental::atan(&self.fixed(scale), scale))
+    }
+
+    fn pi() -> Result<Self, NumberError> {
+        Self::correctly_rounded(transcendental::pi)
+    }
+
+    fn e() -> Result<Self, NumberError> {
+        Self::correctly_rounded(|scale| transcendental::exp(&BigInt::from(10).pow(scale), scale))
+    }
+
+    fn abs(self) -> Result<Self, NumberError> {
+        self.value.checked_abs().map(Self::from_raw).ok_or(NumberError::Overflow)
+    }
+
+    fn sign(self) -> Result<Self, NumberError> {
+        Ok(Self::from_raw(self.value.signum() * Self::UNIT))
+    }
+
+    fn minimum(self, other: Self) -> Result<Self, NumberError> {
+        Ok(Ord::min(self, other))
+    }
+
+    fn maximum(self, other: Self) -> Result<Self, NumberError> {
+        Ok(Ord::max(self, other))
+    }
+
+    fn floor(self) -> Result<Self, NumberError> {
+        self.integer(Rounding::Floor)
+    }
+
+    fn ceil(self) -> Result<Self, NumberError> {
+        self.integer(Rounding::Ceiling)
+    }
+
+    /// Rounds half away from zero.
+    fn round(self) -> Result<Self, NumberError> {
+        self.integer(Rounding::HalfUp)
+    }
+
+    fn trunc(self) -> Result<Self, NumberError> {
+        self.integer(Rounding::Down)
+    }
+}
@@ -0,0 +1,293 @@
+use crate::decimal::Rounding;
+use num::complex::Complex64;
+use num::{BigInt, BigRational, Integer, Signed, Zero};
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// Fractional digits searched for the repeating part of a decimal expansion before it is cut off.
+const EXPANSION_DIGITS: usize = 100;
+
+/// How the digits of a number are laid out.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Notation {
+    /// The digits the type displays itself with.
+    #[default]
+    Plain,
+    /// Exactly this many digits after the decimal mark.
+    Fixed(usize),
+    /// This many significant digits without an exponent.
+    Significant(usize),
+    /// One digit before the decimal mark and an exponent like `1.5e-7`, optionally with this many significant digits.
+    Scientific(Option<usize>),
+    /// Like [`Notation::Scientific`] but with an exponent that is a multiple of three, like `150e-9`.
+    Engineering(Option<usize>),
+}
+
+/// How exact fractions are written.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Fraction {
+    /// In lowest terms, like `7/2`.
+    #[default]
+    Reduced,
+    /// An integer followed by a proper fraction, like `3 1/2`.
+    Mixed,
+    /// Decimal digits with the repeating part in parentheses like `0.1(6)`, or laid out by the [`Notation`] unless it is plain.
+    Decimal,
+}
+
+/// How complex numbers are written.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum ComplexForm {
+    /// Real and imaginary part, like `1+2i`.
+    #[default]
+    Rectangular,
+    /// Magnitude and angle in radians, like `2.236∠1.107`.
+    Polar,
+}
+
+/// Controls how results are written, rounding ties away from zero.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Format {
+    pub notation: Notation,
+    /// Separates groups of three integer digits, like `1,000,000`.
+    pub separator: Option<char>,
+    pub decimal_mark: char,
+    pub fraction: Fraction,
+    pub complex: ComplexForm,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Self {
+            notation: Notation::Plain,
+            separator: None,
+            decimal_mark: '.',
+            fraction: Fraction::Reduced,
+            complex: ComplexForm::Rectangular,
+        }
+    }
+}
+
+impl Format {
+    /// Formats the value if it displays as a decimal numeral like `-12.5`, anything else like `NaN` is kept as is.
+    pub fn apply<T: Display>(&self, value: &T) -> String {
+        let text = value.to_string();
+        self.numeral(&text).unwrap_or(text)
+    }
+
+    /// Formats an exact fraction according to the [`Fraction`] style.
+    pub fn ratio(&self, value: &BigRational) -> String {
+        match self.fraction {
+            Fraction::Reduced => value.to_string(),
+            Fraction::Mixed => {
+                let whole = value.trunc();
+                match value.is_integer() || whole.is_zero() {
+                    true => value.to_string(),
+                    false => format!("{} {}", whole, (value - &whole).abs()),
+                }
+            }
+            Fraction::Decimal => {
+                let sign = if value.is_negative() { "-" } else { "" };
+                if self.notation != Notation::Plain {
+                    let limit = value.denom().to_string().len() + EXPANSION_DIGITS + self.digits();
+                    let (integer, fraction, _) = expand(value, limit, false);
+                    return self.numeral(&format!("{sign}{integer}.{fraction}")).unwrap_or_default();
+                }
+                let (integer, mut fraction, tail) = expand(value, EXPANSION_DIGITS, true);
+                match tail {
+                    Tail::Exact => {}
+                    Tail::Repeating(start) => {
+                        fraction.insert(start, '(');
+                        fraction.push(')');
+                    }
+                    Tail::Truncated => fraction.push('…'),
+                }
+                match fraction.is_empty() {
+                    true => format!("{sign}{}", self.mark(&integer)),
+                    false => format!("{sign}{}", self.mark(&format!("{integer}.{fraction}"))),
+                }
+            }
+        }
+    }
+
+    /// Formats a complex number in rectangular or polar form, a part that is zero is left out of the rectangular form.
+    pub fn complex(&self, value: &Complex64) -> String {
+        match self.complex {
+            ComplexForm::Polar => format!("{}∠{}", self.apply(&value.norm()), self.apply(&value.arg())),
+            ComplexForm::Rectangular if value.im == 0.0 => self.apply(&value.re),
+            ComplexForm::Rectangular => {
+                let imaginary = match self.apply(&value.im.abs()).as_str() {
+                    "1" => String::new(),
+                    magnitude => magnitude.to_string(),
+                };
+                let sign = if value.im < 0.0 { "-" } else { "+" };
+                match value.re == 0.0 {
+                    true => format!("{}{imaginary}i", sign.trim_start_matches('+')),
+                    false => format!("{}{sign}{imaginary}i", self.apply(&value.re)),
+                }
+            }
+        }
+    }
+
+    /// The number of digits the notation asks for.
+    fn digits(&self) -> usize {
+        match self.notation {
+            Notation::Plain | Notation::Scientific(None) | Notation::Engineering(None) => 0,
+            Notation::Fixed(digits) | Notation::Significant(digits) | Notation::Scientific(Some(digits)) | Notation::Engineering(Some(digits)) => digits,
+        }
+    }
+
+    /// Formats a decimal numeral, `None` if `text` is not one.
+    pub fn numeral(&self, text: &str) -> Option<String> {
+        let (negative, unsigned) = match text.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, text),
+        };
+        let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        if integer.is_empty() || !integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) || unsigned.ends_with('.') {
+            return None;
+        }
+        let digits = Digits::new(format!("{integer}{fraction}"), -(fraction.len() as i64));
+        let (body, exponent) = match self.notation {
+            Notation::Plain => (unsigned.to_string(), None),
+            Notation::Fixed(decimals) => (digits.round(-(decimals as i64)).write(decimals), None),
+            Notation::Significant(count) => {
+                let rounded = digits.significant(count);
+                (rounded.write((count as i64 - rounded.magnitude()).max(0) as usize), None)
+            }
+            Notation::Scientific(count) => digits.exponential(count, 1),
+            Notation::Engineering(count) => digits.exponential(count, 3),
+        };
+        let sign = if negative && body.chars().any(|c| c.is_ascii_digit() && c != '0') { "-" } else { "" };
+        let exponent = exponent.map_or(String::new(), |exponent| format!("e{exponent}"));
+        Some(format!("{sign}{}{exponent}", self.mark(&body)))
+    }
+
+    /// Groups the integer digits and replaces the decimal point.
+    fn mark(&self, body: &str) -> String {
+        let (integer, fraction) = body.split_once('.').map_or((body, None), |(integer, fraction)| (integer, Some(fraction)));
+        let mut grouped = String::new();
+        for (i, digit) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i) % 3 == 0 {
+                grouped.extend(self.separator);
+            }
+            grouped.push(digit);
+        }
+        match fraction {
+            Some(fraction) => format!("{grouped}{}{fraction}", self.decimal_mark),
+            None => grouped,
+        }
+    }
+}
+
+/// How the decimal expansion of a fraction ends.
+enum Tail {
+    Exact,
+    /// The fractional digits from this position on repeat forever.
+    Repeating(usize),
+    /// The expansion continues after the last digit.
+    Truncated,
+}
+
+/// The integer and up to `limit` fractional digits of the magnitude of `value`, looking for a repeating part if `repeating` is set.
+fn expand(value: &BigRational, limit: usize, repeating: bool) -> (String, String, Tail) {
+    let denominator = value.denom();
+    let (integer, mut remainder) = value.numer().abs().div_rem(denominator);
+    let mut seen = HashMap::new();
+    let mut fraction = String::new();
+    while !remainder.is_zero() {
+        if let Some(&start) = seen.get(&remainder) {
+            return (integer.to_string(), fraction, Tail::Repeating(start));
+        }
+        if fraction.len() == limit {
+            return (integer.to_string(), fraction, Tail::Truncated);
+        }
+        if repeating {
+            seen.insert(remainder.clone(), fraction.len());
+        }
+        let (digit, rest) = (remainder * BigInt::from(10)).div_rem(denominator);
+        fraction.push_str(&digit.to_string());
+        remainder = rest;
+    }
+    (integer.to_string(), fraction, Tail::Exact)
+}
+
+/// The unsigned value `digits * 10^exponent`.
+#[derive(Debug, Clone)]
+struct Digits {
+    digits: String,
+    exponent: i64,
+}
+
+impl Digits {
+    fn new(digits: String, exponent: i64) -> Self {
+        let digits = digits.trim_start_matches('0').to_string();
+        Self { digits, exponent }
+    }
+
+    /// The power of ten above the leading digit, zero counts as the single digit `0`.
+    fn magnitude(&self) -> i64 {
+        match self.digits.len() {
+            0 => 1,
+            length => length as i64 + self.exponent,
+        }
+    }
+
+    /// Rounds to a multiple of `10^exponent`.
+    fn round(&self, exponent: i64) -> Self {
+        let value = self.digits.parse::<BigInt>().unwrap_or_default();
+        let shift = exponent - self.exponent;
+        let value = match shift {
+            ..=0 => value * BigInt::from(10).pow(shift.unsigned_abs() as u32),
+            _ => Rounding::HalfUp.divide(&value, &BigInt::from(10).pow(shift as u32)),
+        };
+        Digits::new(value.to_string(), exponent)
+    }
+
+    /// Rounds to `count` significant digits, a carry like `9.99` to `10.0` drops the last digit.
+    fn significant(&self, count: usize) -> Self {
+        let rounded = self.round(self.magnitude() - count.max(1) as i64);
+        match rounded.magnitude() > self.magnitude() {
+            true => rounded.round(rounded.magnitude() - count.max(1) as i64),
+            false => rounded,
+        }
+    }
+
+    /// Writes the value with `decimals` digits after the decimal point.
+    fn write(&self, decimals: usize) -> String {
+        let mut digits = self.digits.clone();
+        match self.exponent {
+            0.. => digits.push_str(&"0".repeat(self.exponent as usize)),
+            exponent => {
+                let padding = (exponent.unsigned_abs() as usize + 1).saturating_sub(digits.len());
+                digits.insert_str(0, &"0".repeat(padding));
+            }
+        }
+        let point = digits.len() - (-self.exponent).max(0) as usize;
+        let (integer, fraction) = digits.split_at(point);
+        let integer = if integer.is_empty() { "0" } else { integer };
+        match decimals {
+            0 => integer.to_string(),
+            _ => format!("{integer}.{:0<decimals$}", &fraction[..fraction.len().min(decimals)]),
+        }
+    }
+
+    /// A mantissa with up to `step` integer digits and an exponent that is a multiple of `step`.
+    fn exponential(&self, count: Option<usize>, step: i64) -> (String, Option<i64>) {
+        if self.digits.is_empty() {
+            return (Digits::new(String::new(), 0).write(count.map_or(0, |count| count.saturating_sub(1))), Some(0));
+        }
+        let rounded = match count {
+            Some(count) => self.significant(count),
+            None => Digits::new(
+                self.digits.trim_end_matches('0').to_string(),
+                self.exponent + (self.digits.len() - self.digits.trim_end_matches('0').len()) as i64,
+            ),
+        };
+        let exponent = (rounded.magnitude() - 1).div_euclid(step) * step;
+        let digits = count.unwrap_or(rounded.digits.len());
+        let integer_digits = rounded.magnitude() - exponent;
+        let mantissa = Digits::new(rounded.digits.clone(), rounded.exponent - exponent);
+        (mantissa.write((digits as i64 - integer_digits).max(0) as usize), Some(exponent))
+    }
+}
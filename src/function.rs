@@ -0,0 +1,83 @@
+use crate::number::NumberError;
+use std::collections::HashMap;
+use std::fmt::{Debug, Display, Formatter};
+
+/// The number of arguments a function accepts.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Arity {
+    Exact(usize),
+    AtLeast(usize),
+}
+
+impl Arity {
+    pub fn accepts(&self, count: usize) -> bool {
+        match self {
+            Arity::Exact(n) => count == *n,
+            Arity::AtLeast(n) => count >= *n,
+        }
+    }
+}
+
+impl Display for Arity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let plural = |n: usize| if n == 1 { "" } else { "s" };
+        match self {
+            Arity::Exact(n) => write!(f, "{n} argument{}", plural(*n)),
+            Arity::AtLeast(n) => write!(f, "at least {n} argument{}", plural(*n)),
+        }
+    }
+}
+
+type Body<T> = Box<dyn Fn(&[T]) -> Result<T, NumberError>>;
+
+/// A native function callable from expressions, the arguments are checked against its arity before it is called.
+pub struct Function<T> {
+    pub arity: Arity,
+    body: Body<T>,
+}
+
+impl<T> Function<T> {
+    pub fn call(&self, args: &[T]) -> Result<T, NumberError> {
+        (self.body)(args)
+    }
+}
+
+/// The functions available to an expression, looked up by name.
+pub struct Functions<T> {
+    functions: HashMap<String, Function<T>>,
+}
+
+impl<T> Functions<T> {
+    pub fn new() -> Self {
+        Self { functions: HashMap::new() }
+    }
+
+    /// Registers `body` under `name`, replacing any function previously registered with that name.
+    pub fn register<F>(&mut self, name: impl Into<String>, arity: Arity, body: F) -> &mut Self
+    where
+        F: Fn(&[T]) -> Result<T, NumberError> + 'static,
+    {
+        self.functions.insert(name.into(), Function { arity, body: Box::new(body) });
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Function<T>> {
+        self.functions.get(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        self.functions.keys()
+    }
+}
+
+impl<T> Default for Functions<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Debug for Functions<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.functions.keys()).finish()
+    }
+}
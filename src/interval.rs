@@ -0,0 +1,418 @@
+use crate::builtins::Elementary;
+use crate::number::{Number, NumberError};
+use crate::parse::Literal;
+use num::BigRational;
+use std::cmp::Ordering;
+use std::f64::consts::{E, FRAC_PI_2, PI, TAU};
+use std::fmt::{Display, Formatter};
+
+/// A closed interval `[lo, hi]` of `f64` bounds that is guaranteed to contain the exact result.
+///
+/// Every operation rounds its lower bound down and its upper bound up, literals that are not exactly
+/// representable become the two floats around them. Dividing by an interval containing zero results in
+/// an unbounded interval, only dividing by exactly zero fails with [`NumberError::DivisionByZero`]. A result
+/// that overflows is `[f64::MAX, inf]`, and an undefined one like `inf - inf` is unbounded on both sides.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Interval {
+    lo: f64,
+    hi: f64,
+}
+
+/// Rounds a result of the functions of `f64`, which are not correctly rounded, down by one unit in the last place.
+fn down(value: f64) -> f64 {
+    value.next_down()
+}
+
+/// See [`down`].
+fn up(value: f64) -> f64 {
+    value.next_up()
+}
+
+/// The exact sum is `sum + error`, Knuth's TwoSum.
+fn add_error(a: f64, b: f64, sum: f64) -> f64 {
+    let b_virtual = sum - a;
+    (a - (sum - b_virtual)) + (b - b_virtual)
+}
+
+/// A lower bound computed from `a` and `b`. Finite operands that overflow to infinity only show the exact
+/// result is above `f64::MAX`, and an undefined result like `∞ - ∞` bounds nothing.
+fn lower(bound: f64, a: f64, b: f64) -> f64 {
+    match bound {
+        _ if bound.is_nan() => f64::NEG_INFINITY,
+        f64::INFINITY if a.is_finite() && b.is_finite() => f64::MAX,
+        _ => bound,
+    }
+}
+
+/// See [`lower`].
+fn upper(bound: f64, a: f64, b: f64) -> f64 {
+    match bound {
+        _ if bound.is_nan() => f64::INFINITY,
+        f64::NEG_INFINITY if a.is_finite() && b.is_finite() => -f64::MAX,
+        _ => bound,
+    }
+}
+
+fn add_down(a: f64, b: f64) -> f64 {
+    let sum = a + b;
+    if sum.is_finite() && add_error(a, b, sum) < 0.0 {
+        return sum.next_down();
+    }
+    lower(sum, a, b)
+}
+
+fn add_up(a: f64, b: f64) -> f64 {
+    let sum = a + b;
+    if sum.is_finite() && add_error(a, b, sum) > 0.0 {
+        return sum.next_up();
+    }
+    upper(sum, a, b)
+}
+
+/// Zero times an infinite bound is zero, the infinity only stands for an unbounded side.
+fn product(a: f64, b: f64) -> f64 {
+    if a == 0.0 || b == 0.0 {
+        0.0
+    } else {
+        a * b
+    }
+}
+
+fn mul_down(a: f64, b: f64) -> f64 {
+    let product = product(a, b);
+    // the fused multiply-add computes the rounding error of the product exactly
+    if product.is_finite() && a.mul_add(b, -product) < 0.0 {
+        return product.next_down();
+    }
+    lower(product, a, b)
+}
+
+fn mul_up(a: f64, b: f64) -> f64 {
+    let product = product(a, b);
+    if product.is_finite() && a.mul_add(b, -product) > 0.0 {
+        return product.next_up();
+    }
+    upper(product, a, b)
+}
+
+/// Whether the exact quotient is above the rounded `quotient`, its remainder `a - quotient * b` is exact.
+fn quotient_below(a: f64, b: f64, quotient: f64) -> Option<bool> {
+    if !quotient.is_finite() || !b.is_finite() || quotient == 0.0 && a == 0.0 {
+        return None;
+    }
+    let remainder = -quotient.mul_add(b, -a);
+    (remainder != 0.0).then_some((remainder > 0.0) == (b > 0.0))
+}
+
+/// The quotient of two unbounded sides can be any value of the sign they share.
+fn div_down(a: f64, b: f64) -> f64 {
+    if a.is_infinite() && b.is_infinite() {
+        return if (a > 0.0) == (b > 0.0) { 0.0 } else { f64::NEG_INFINITY };
+    }
+    let quotient = a / b;
+    match quotient_below(a, b, quotient) {
+        Some(false) => quotient.next_down(),
+        _ => lower(quotient, a, b),
+    }
+}
+
+fn div_up(a: f64, b: f64) -> f64 {
+    if a.is_infinite() && b.is_infinite() {
+        return if (a > 0.0) == (b > 0.0) { f64::INFINITY } else { -0.0 };
+    }
+    let quotient = a / b;
+    match quotient_below(a, b, quotient) {
+        Some(true) => quotient.next_up(),
+        _ => upper(quotient, a, b),
+    }
+}
+
+/// `value^power` of a non-negative value by squaring, each step rounded in the same direction.
+fn powi(value: f64, power: u64, mul: fn(f64, f64) -> f64) -> f64 {
+    let (mut result, mut base, mut power) = (1.0, value, power);
+    while power > 0 {
+        if power & 1 == 1 {
+            result = mul(result, base);
+        }
+        base = mul(base, base);
+        power >>= 1;
+    }
+    result
+}
+
+impl Interval {
+    /// Creates `[lo, hi]`.
+    ///
+    /// # Panics
+    ///
+    /// If a bound is `NaN` or `lo > hi`.
+    pub fn new(lo: f64, hi: f64) -> Self {
+        assert!(lo <= hi, "the interval [{lo}, {hi}] is empty");
+        Self { lo, hi }
+    }
+
+    /// The interval containing only `value`.
+    pub fn point(value: f64) -> Self {
+        Self::new(value, value)
+    }
+
+    pub fn lo(&self) -> f64 {
+        self.lo
+    }
+
+    pub fn hi(&self) -> f64 {
+        self.hi
+    }
+
+    pub fn width(&self) -> f64 {
+        self.hi - self.lo
+    }
+
+    pub fn contains(&self, value: f64) -> bool {
+        self.lo <= value && value <= self.hi
+    }
+
+    /// Applies a function that increases monotonically, widening the result by the error of `f64`'s functions.
+    fn increasing(self, f: fn(f64) -> f64) -> Self {
+        Self::new(down(f(self.lo)), up(f(self.hi)))
+    }
+
+    /// Fails unless the interval lies within `[lo, hi]`.
+    fn within(self, lo: f64, hi: f64, operation: &str) -> Result<Self, NumberError> {
+        if self.lo < lo || self.hi > hi {
+            return Err(NumberError::Domain(format!("{operation} is not defined on all of {self}")));
+        }
+        Ok(self)
+    }
+
+    /// `sin` if `peak` is `π/2` or `cos` if it is zero, the bounds include the extrema the interval covers.
+    fn periodic(self, f: fn(f64) -> f64, peak: f64) -> Self {
+        if self.width() >= TAU || self.width().is_nan() {
+            return Self::new(-1.0, 1.0);
+        }
+        // the extrema are only located approximately, so ones just outside the interval count as well
+        let slack = 1e-12 * self.lo.abs().max(self.hi.abs()).max(1.0);
+        let covers = |extremum: f64| {
+            let turn = ((self.lo - slack - extremum) / TAU).ceil();
+            extremum + turn * TAU <= self.hi + slack
+        };
+        let (a, b) = (f(self.lo), f(self.hi));
+        let lo = if covers(peak - PI) { -1.0 } else { down(a.min(b)).max(-1.0) };
+        let hi = if covers(peak) { 1.0 } else { up(a.max(b)).min(1.0) };
+        Self::new(lo, hi)
+    }
+
+    /// Raises to a non-negative integer power.
+    fn powi(self, power: u64) -> Self {
+        let (lo, hi) = (self.lo, self.hi);
+        match (power.is_multiple_of(2), lo >= 0.0, hi <= 0.0) {
+            (_, true, _) => Self::new(powi(lo, power, mul_down), powi(hi, power, mul_up)),
+            (true, _, true) => Self::new(powi(-hi, power, mul_down), powi(-lo, power, mul_up)),
+            (true, _, _) => Self::new(0.0, powi(lo.abs().max(hi), power, mul_up)),
+            (false, _, _) => Self::new(-powi(-lo, power, mul_up), if hi < 0.0 { -powi(-hi, power, mul_down) } else { powi(hi, power, mul_up) }),
+        }
+    }
+
+    fn is_point(&self) -> bool {
+        self.lo == self.hi
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}, {}]", self.lo, self.hi)
+    }
+}
+
+impl Number for Interval {
+    fn from_literal(literal: &Literal) -> Option<Self> {
+        let nearest = f64::from_literal(literal)?;
+        let exact = BigRational::from_literal(literal);
+        Some(match exact.as_ref().zip(BigRational::from_float(nearest)).map(|(exact, nearest)| exact.cmp(&nearest)) {
+            Some(Ordering::Equal) => Self::point(nearest),
+            Some(Ordering::Less) => Self::new(nearest.next_down(), nearest),
+            Some(Ordering::Greater) => Self::new(nearest, nearest.next_up()),
+            None => Self::new(nearest.next_down(), nearest.next_up()),
+        })
+    }
+
+    fn try_add(self, rhs: Self) -> Result<Self, NumberError> {
+        Ok(Self::new(add_down(self.lo, rhs.lo), add_up(self.hi, rhs.hi)))
+    }
+
+    fn try_sub(self, rhs: Self) -> Result<Self, NumberError> {
+        Ok(Self::new(add_down(self.lo, -rhs.hi), add_up(self.hi, -rhs.lo)))
+    }
+
+    fn try_mul(self, rhs: Self) -> Result<Self, NumberError> {
+        let pairs = [(self.lo, rhs.lo), (self.lo, rhs.hi), (self.hi, rhs.lo), (self.hi, rhs.hi)];
+        let lo = pairs.iter().map(|&(a, b)| mul_down(a, b)).fold(f64::INFINITY, f64::min);
+        let hi = pairs.iter().map(|&(a, b)| mul_up(a, b)).fold(f64::NEG_INFINITY, f64::max);
+        Ok(Self::new(lo, hi))
+    }
+
+    /// A divisor containing zero results in the unbounded interval enclosing both sides of the pole.
+    fn try_div(self, rhs: Self) -> Result<Self, NumberError> {
+        if rhs.lo == 0.0 && rhs.hi == 0.0 {
+            return Err(NumberError::DivisionByZero);
+        }
+        if rhs.lo > 0.0 || rhs.hi < 0.0 {
+            let pairs = [(self.lo, rhs.lo), (self.lo, rhs.hi), (self.hi, rhs.lo), (self.hi, rhs.hi)];
+            let lo = pairs.iter().map(|&(a, b)| div_down(a, b)).fold(f64::INFINITY, f64::min);
+            let hi = pairs.iter().map(|&(a, b)| div_up(a, b)).fold(f64::NEG_INFINITY, f64::max);
+            return Ok(Self::new(lo, hi));
+        }
+        let unbounded = Self::new(f64::NEG_INFINITY, f64::INFINITY);
+        Ok(match (self.lo > 0.0, self.hi < 0.0, rhs.lo == 0.0, rhs.hi == 0.0) {
+            _ if self.lo == 0.0 && self.hi == 0.0 => self,
+            (true, _, true, _) => Self::new(div_down(self.lo, rhs.hi), f64::INFINITY),
+            (true, _, _, true) => Self::new(f64::NEG_INFINITY, div_up(self.lo, rhs.lo)),
+            (_, true, true, _) => Self::new(f64::NEG_INFINITY, div_up(self.hi, rhs.hi)),
+            (_, true, _, true) => Self::new(div_down(self.hi, rhs.lo), f64::INFINITY),
+            _ => unbounded,
+        })
+    }
+
+    fn try_rem(self, _rhs: Self) -> Result<Self, NumberError> {
+        Err(NumberError::Unsupported("remainder"))
+    }
+
+    /// Integer exponents multiply with directed rounding, other exponents need a positive base.
+    fn try_pow(self, rhs: Self) -> Result<Self, NumberError> {
+        if rhs.is_point() && rhs.lo.fract() == 0.0 && rhs.lo.abs() < u64::MAX as f64 {
+            let power = self.powi(rhs.lo.abs() as u64);
+            return if rhs.lo < 0.0 { Self::point(1.0).try_div(power) } else { Ok(power) };
+        }
+        if self.lo <= 0.0 {
+            return Err(NumberError::Domain(format!("{self} cannot be raised to the non-integer power {rhs}, it is not positive")));
+        }
+        rhs.try_mul(self.ln()?)?.exp()
+    }
+
+    fn try_neg(self) -> Result<Self, NumberError> {
+        Ok(Self::new(-self.hi, -self.lo))
+    }
+
+    /// Only a single point is a real number.
+    fn to_float(&self) -> Option<f64> {
+        (self.lo == self.hi).then_some(self.lo)
+    }
+
+    fn from_bool(value: bool) -> Self {
+        Self::point(u8::from(value).into())
+    }
+
+    /// Only intervals that do not overlap, or are the same point, are ordered.
+    fn try_cmp(&self, other: &Self) -> Result<Option<Ordering>, NumberError> {
+        match () {
+            _ if self.hi < other.lo => Ok(Some(Ordering::Less)),
+            _ if self.lo > other.hi => Ok(Some(Ordering::Greater)),
+            _ if self.is_point() && self == other => Ok(Some(Ordering::Equal)),
+            _ => Err(NumberError::Domain(format!("{self} and {other} overlap, their order is uncertain"))),
+        }
+    }
+
+    fn try_interval(self, hi: Self) -> Result<Self, NumberError> {
+        if self.lo > hi.hi {
+            return Err(NumberError::Domain(format!("the interval [{}, {}] is empty", self.lo, hi.hi)));
+        }
+        Ok(Self::new(self.lo, hi.hi))
+    }
+}
+
+/// Bounds use the functions of `f64` widened by a unit in the last place, which covers their error.
+impl Elementary for Interval {
+    fn sqrt(self) -> Result<Self, NumberError> {
+        let root = self.within(0.0, f64::INFINITY, "square root")?.increasing(f64::sqrt);
+        Ok(Self::new(root.lo.max(0.0), root.hi))
+    }
+
+    fn cbrt(self) -> Result<Self, NumberError> {
+        Ok(self.increasing(f64::cbrt))
+    }
+
+    fn exp(self) -> Result<Self, NumberError> {
+        let power = self.increasing(f64::exp);
+        Ok(Self::new(power.lo.max(0.0), power.hi))
+    }
+
+    fn ln(self) -> Result<Self, NumberError> {
+        Ok(self.within(f64::MIN_POSITIVE, f64::INFINITY, "logarithm")?.increasing(f64::ln))
+    }
+
+    fn log10(self) -> Result<Self, NumberError> {
+        Ok(self.within(f64::MIN_POSITIVE, f64::INFINITY, "logarithm")?.increasing(f64::log10))
+    }
+
+    fn log2(self) -> Result<Self, NumberError> {
+        Ok(self.within(f64::MIN_POSITIVE, f64::INFINITY, "logarithm")?.increasing(f64::log2))
+    }
+
+    fn sin(self) -> Result<Self, NumberError> {
+        Ok(self.periodic(f64::sin, FRAC_PI_2))
+    }
+
+    fn cos(self) -> Result<Self, NumberError> {
+        Ok(self.periodic(f64::cos, 0.0))
+    }
+
+    fn atan(self) -> Result<Self, NumberError> {
+        let angle = self.increasing(f64::atan);
+        Ok(Self::new(angle.lo.max(-up(FRAC_PI_2)), angle.hi.min(up(FRAC_PI_2))))
+    }
+
+    fn sinh(self) -> Result<Self, NumberError> {
+        Ok(self.increasing(f64::sinh))
+    }
+
+    fn tanh(self) -> Result<Self, NumberError> {
+        let value = self.increasing(f64::tanh);
+        Ok(Self::new(value.lo.max(-1.0), value.hi.min(1.0)))
+    }
+
+    fn asinh(self) -> Result<Self, NumberError> {
+        Ok(self.increasing(f64::asinh))
+    }
+
+    fn pi() -> Result<Self, NumberError> {
+        // the closest float is below π
+        Ok(Self::new(PI, up(PI)))
+    }
+
+    fn e() -> Result<Self, NumberError> {
+        // the closest float is below e
+        Ok(Self::new(E, up(E)))
+    }
+
+    fn abs(self) -> Result<Self, NumberError> {
+        Ok(match (self.lo >= 0.0, self.hi <= 0.0) {
+            (true, _) => self,
+            (_, true) => Self::new(-self.hi, -self.lo),
+            _ => Self::new(0.0, self.hi.max(-self.lo)),
+        })
+    }
+
+    fn minimum(self, other: Self) -> Result<Self, NumberError> {
+        Ok(Self::new(self.lo.min(other.lo), self.hi.min(other.hi)))
+    }
+
+    fn maximum(self, other: Self) -> Result<Self, NumberError> {
+        Ok(Self::new(self.lo.max(other.lo), self.hi.max(other.hi)))
+    }
+
+    fn floor(self) -> Result<Self, NumberError> {
+        Ok(Self::new(self.lo.floor(), self.hi.floor()))
+    }
+
+    fn ceil(self) -> Result<Self, NumberError> {
+        Ok(Self::new(self.lo.ceil(), self.hi.ceil()))
+    }
+
+    fn round(self) -> Result<Self, NumberError> {
+        Ok(Self::new(self.lo.round(), self.hi.round()))
+    }
+
+    fn trunc(self) -> Result<Self, NumberError> {
+        Ok(Self::new(self.lo.trunc(), self.hi.trunc()))
+    }
+}
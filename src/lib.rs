@@ -1,16 +1,31 @@
+use crate::builtins::Elementary;
+use crate::dual::Dual;
 use crate::eval::{eval_expr, EvalError};
-use crate::parse::{parse_string, tokenize, ParseError};
+use crate::function::Functions;
+use crate::number::Number;
+use crate::parse::{parse_string, tokenize, ParseError, Span, Syntax};
 use log::{debug, info};
-use num::traits::Pow;
 use std::any::type_name;
 use std::collections::HashMap;
 use std::fmt::{Debug, Display, Formatter};
-use std::ops::{Add, Div, Mul, Rem, Sub};
-use std::str::FromStr;
 
+pub mod big_decimal;
+pub mod big_int;
+pub mod builtins;
+pub mod complex;
 pub mod decimal;
+pub mod dual;
 pub mod eval;
+pub mod fixed;
+pub mod format;
+pub mod function;
+pub mod interval;
+pub mod measured;
+pub mod modular;
+pub mod number;
 pub mod parse;
+pub mod rational;
+pub mod transcendental;
 #[cfg(test)]
 mod tests;
 
@@ -20,6 +35,16 @@ pub enum Error {
     EvalError(EvalError),
 }
 
+impl Error {
+    /// The range of the input that caused this error.
+    pub fn span(&self) -> Span {
+        match self {
+            Error::ParseError(p) => p.span(),
+            Error::EvalError(e) => e.span(),
+        }
+    }
+}
+
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -48,6 +73,9 @@ impl From<EvalError> for Error {
     ($ex:expr, $vars:expr) => {
             $crate::solve_vars($ex, $vars)
     };
+    ($ex:expr, $vars:expr, $fns:expr) => {
+            $crate::solve_vars_with($ex, $vars, $fns)
+    };
 }
 
 pub fn solve(input: String) -> Result<f64, Error> {
@@ -55,24 +83,40 @@ pub fn solve(input: String) -> Result<f64, Error> {
 }
 
 pub fn solve_vars(input: String, variables: &HashMap<String, f64>) -> Result<f64, Error> {
-    solve_vars_with::<f64>(input, variables)
+    solve_vars_with::<f64>(input, variables, &Functions::builtins())
+}
+
+/// Evaluates the expression and its derivative by `variable` at the given values of the variables.
+pub fn derivative_at(input: String, variable: &str, variables: &HashMap<String, f64>) -> Result<Dual, Error> {
+    let variables = variables
+        .iter()
+        .map(|(name, &value)| (name.clone(), if name == variable { Dual::variable(value) } else { Dual::constant(value) }))
+        .collect();
+    solve_vars_with(input, &variables, &Functions::builtins())
+}
+
+pub fn solve_with<T>(input: String) -> Result<T, Error>
+where
+    T: Elementary + 'static + Debug + Clone,
+{
+    solve_vars_with(input, &HashMap::new(), &Functions::builtins())
 }
 
-pub fn solve_with<T: PartialEq>(input: String) -> Result<T, Error>
+pub fn solve_vars_with<T>(input: String, variables: &HashMap<String, T>, functions: &Functions<T>) -> Result<T, Error>
 where
-    T: Pow<T, Output = T> + Debug + FromStr + Copy + Sized + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T> + Rem<Output = T>,
+    T: Number + Debug + Clone,
 {
-    solve_vars_with(input, &HashMap::new())
+    solve_with_syntax(input, variables, functions, &Syntax::default())
 }
 
-pub fn solve_vars_with<T: PartialEq>(input: String, variables: &HashMap<String, T>) -> Result<T, Error>
+pub fn solve_with_syntax<T>(input: String, variables: &HashMap<String, T>, functions: &Functions<T>, syntax: &Syntax) -> Result<T, Error>
 where
-    T: Pow<T, Output = T> + Debug + FromStr + Copy + Sized + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T> + Rem<Output = T>,
+    T: Number + Debug + Clone,
 {
-    info!("Solving equation {input} with type {} and variables {variables:?}", type_name::<T>());
-    let tokenized_input = tokenize(input)?;
+    info!("Solving equation {input} with type {}, variables {variables:?}, functions {functions:?} and {syntax:?}", type_name::<T>());
+    let tokenized_input = tokenize(input, syntax, functions)?;
     debug!("Tokenized input: {tokenized_input:?}");
     let parsed_input = parse_string(tokenized_input)?;
     debug!("Parsed input: {parsed_input:?}");
-    Ok(eval_expr::<T>(&parsed_input, variables)?)
+    Ok(eval_expr::<T>(&parsed_input, variables, functions)?)
 }
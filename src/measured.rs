@@ -0,0 +1,199 @@
+use crate::builtins::Chain;
+use crate::number::{Number, NumberError};
+use crate::parse::Literal;
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+
+thread_local! {
+    static SOURCES: Cell<u64> = const { Cell::new(0) };
+}
+
+/// A value with a standard uncertainty, written `9.81±0.02` or `9.81 +/- 0.02`.
+///
+/// Uncertainties propagate to first order: every `±` is an independent measurement, and a value keeps how
+/// much each measurement it depends on contributes to it. Contributions of the same measurement add up
+/// linearly and independent ones in quadrature, so `x - x` is exactly zero and `x * x` is as uncertain as
+/// `x^2`. Values are ordered by their central value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measured {
+    value: f64,
+    /// The standard uncertainty contributed by each measurement, sorted by the measurement.
+    contributions: Vec<(u64, f64)>,
+}
+
+impl Measured {
+    /// An exact value.
+    pub fn exact(value: f64) -> Self {
+        Self { value, contributions: Vec::new() }
+    }
+
+    /// A new measurement independent of every other one.
+    ///
+    /// # Panics
+    ///
+    /// If the uncertainty is negative.
+    pub fn new(value: f64, uncertainty: f64) -> Self {
+        assert!(uncertainty >= 0.0, "the uncertainty {uncertainty} is negative");
+        Self::exact(value).with(uncertainty)
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// The standard uncertainty, the contributions of all measurements added in quadrature.
+    pub fn uncertainty(&self) -> f64 {
+        self.contributions.iter().fold(0.0, |sum, (_, contribution)| f64::hypot(sum, *contribution))
+    }
+
+    /// Adds the uncertainty of a new independent measurement.
+    fn with(mut self, uncertainty: f64) -> Self {
+        if uncertainty != 0.0 {
+            let source = SOURCES.get();
+            SOURCES.set(source + 1);
+            self.contributions.push((source, uncertainty));
+        }
+        self
+    }
+
+    /// The linear combination `a·self + b·other` of the contributions, with the given value.
+    fn combine(&self, a: f64, other: &Self, b: f64, value: f64) -> Self {
+        let (mut left, mut right) = (self.contributions.iter().peekable(), other.contributions.iter().peekable());
+        let mut contributions = Vec::with_capacity(self.contributions.len() + other.contributions.len());
+        loop {
+            let next = match (left.peek(), right.peek()) {
+                (Some(&&(l, x)), Some(&&(r, y))) if l == r => {
+                    left.next();
+                    right.next();
+                    (l, a * x + b * y)
+                }
+                (Some(&&(l, x)), Some(&&(r, _))) if l < r => {
+                    left.next();
+                    (l, a * x)
+                }
+                (Some(&&(l, x)), None) => {
+                    left.next();
+                    (l, a * x)
+                }
+                (_, Some(&&(r, y))) => {
+                    right.next();
+                    (r, b * y)
+                }
+                (None, None) => break,
+            };
+            contributions.push(next);
+        }
+        Self { value, contributions }
+    }
+}
+
+impl Display for Measured {
+    /// The uncertainty with two significant figures if it starts with a one and one otherwise, and the value
+    /// rounded to the same decimal place.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let uncertainty = self.uncertainty();
+        if uncertainty == 0.0 || !uncertainty.is_finite() || !self.value.is_finite() {
+            return write!(f, "{} ± {uncertainty}", self.value);
+        }
+        let magnitude = uncertainty.log10().floor() as i32;
+        let figures = if uncertainty / 10f64.powi(magnitude) < 2.0 { 2 } else { 1 };
+        let place = magnitude - figures + 1;
+        if place >= 0 {
+            let unit = 10f64.powi(place);
+            return write!(f, "{} ± {}", (self.value / unit).round() * unit, (uncertainty / unit).round() * unit);
+        }
+        let decimals = -place as usize;
+        write!(f, "{:.decimals$} ± {:.decimals$}", self.value, uncertainty)
+    }
+}
+
+impl Chain for Measured {
+    fn value(&self) -> f64 {
+        self.value
+    }
+
+    fn constant(value: f64) -> Self {
+        Self::exact(value)
+    }
+
+    /// Every contribution is scaled by the derivative.
+    fn chain(&self, value: f64, derivative: f64) -> Self {
+        Self {
+            value,
+            contributions: self.contributions.iter().map(|&(source, contribution)| (source, derivative * contribution)).collect(),
+        }
+    }
+}
+
+impl Number for Measured {
+    fn from_literal(literal: &Literal) -> Option<Self> {
+        f64::from_literal(literal).map(Self::exact)
+    }
+
+    fn try_add(self, rhs: Self) -> Result<Self, NumberError> {
+        Ok(self.combine(1.0, &rhs, 1.0, self.value + rhs.value))
+    }
+
+    fn try_sub(self, rhs: Self) -> Result<Self, NumberError> {
+        Ok(self.combine(1.0, &rhs, -1.0, self.value - rhs.value))
+    }
+
+    fn try_mul(self, rhs: Self) -> Result<Self, NumberError> {
+        Ok(self.combine(rhs.value, &rhs, self.value, self.value * rhs.value))
+    }
+
+    fn try_div(self, rhs: Self) -> Result<Self, NumberError> {
+        if rhs.value == 0.0 {
+            return Err(NumberError::DivisionByZero);
+        }
+        let quotient = self.value / rhs.value;
+        Ok(self.combine(1.0 / rhs.value, &rhs, -quotient / rhs.value, quotient))
+    }
+
+    fn try_rem(self, rhs: Self) -> Result<Self, NumberError> {
+        if rhs.value == 0.0 {
+            return Err(NumberError::DivisionByZero);
+        }
+        let quotient = (self.value / rhs.value).trunc();
+        Ok(self.combine(1.0, &rhs, -quotient, self.value % rhs.value))
+    }
+
+    /// An uncertain exponent needs a positive base, the derivative by the exponent is `ln(x)·x^y`.
+    fn try_pow(self, rhs: Self) -> Result<Self, NumberError> {
+        let power = self.value.powf(rhs.value);
+        let base = if rhs.value == 0.0 { 0.0 } else { rhs.value * self.value.powf(rhs.value - 1.0) };
+        if rhs.contributions.is_empty() {
+            return Ok(self.chain(power, base));
+        }
+        if self.value <= 0.0 {
+            return Err(NumberError::Domain(format!("{self} cannot be raised to the uncertain power {rhs}, it is not positive")));
+        }
+        Ok(self.combine(base, &rhs, power * self.value.ln(), power))
+    }
+
+    fn try_neg(self) -> Result<Self, NumberError> {
+        Ok(self.chain(-self.value, -1.0))
+    }
+
+    /// Only an exact value is a real number.
+    fn to_float(&self) -> Option<f64> {
+        self.contributions.is_empty().then_some(self.value)
+    }
+
+    fn from_bool(value: bool) -> Self {
+        Self::exact(u8::from(value).into())
+    }
+
+    fn try_cmp(&self, other: &Self) -> Result<Option<Ordering>, NumberError> {
+        Ok(self.value.partial_cmp(&other.value))
+    }
+
+    /// The uncertainty itself is taken as exact.
+    fn try_uncertain(self, uncertainty: Self) -> Result<Self, NumberError> {
+        if uncertainty.value < 0.0 || uncertainty.value.is_nan() {
+            return Err(NumberError::Domain(format!("the uncertainty {} is negative", uncertainty.value)));
+        }
+        Ok(self.with(uncertainty.value))
+    }
+}
@@ -0,0 +1,184 @@
+use crate::builtins::Elementary;
+use crate::number::{Number, NumberError};
+use crate::parse::Literal;
+use num::{BigInt, Integer, One, Signed, ToPrimitive, Zero};
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+
+thread_local! {
+    static MODULUS: RefCell<Option<BigInt>> = const { RefCell::new(None) };
+}
+
+/// The modulus [`Modular`] operations on the current thread reduce by, `None` until one is set.
+pub fn modulus() -> Option<BigInt> {
+    MODULUS.with_borrow(Clone::clone)
+}
+
+/// Replaces the modulus [`Modular`] operations on the current thread reduce by.
+///
+/// # Panics
+///
+/// If the modulus is not positive.
+pub fn set_modulus(modulus: BigInt) {
+    assert!(modulus.is_positive(), "the modulus must be positive");
+    MODULUS.set(Some(modulus));
+}
+
+fn current() -> Result<BigInt, NumberError> {
+    modulus().ok_or_else(|| NumberError::Domain("no modulus is set".into()))
+}
+
+/// An integer modulo the thread's [`modulus`], for example `--modulus 1000000007`.
+///
+/// `/` multiplies by the modular inverse and `^` uses fast modular exponentiation. Values are equal and
+/// ordered by their residue in `0..modulus`, but `+ - * % ^` keep the integer they compute until it has more
+/// digits than the [`Context`](crate::decimal::Context) allows. Exponents are never reduced by the modulus,
+/// so `2^(3*5)` is `2^15`, and `2^(0-1)` is the inverse of 2 like `2^-1`. An exponent only known by its
+/// residue, like the result of `/`, is an error.
+#[derive(Debug, Clone)]
+pub struct Modular {
+    value: BigInt,
+    /// Whether the value is the integer the expression computes rather than any representative of its residue.
+    integer: bool,
+}
+
+impl Modular {
+    pub fn new(value: impl Into<BigInt>) -> Self {
+        Self { value: value.into(), integer: true }
+    }
+
+    /// The representative in `0..modulus`, `None` while no modulus is set.
+    pub fn residue(&self) -> Option<BigInt> {
+        modulus().map(|modulus| self.value.mod_floor(&modulus))
+    }
+
+    fn reduce(value: BigInt) -> Result<Self, NumberError> {
+        Ok(Self {
+            value: value.mod_floor(&current()?),
+            integer: false,
+        })
+    }
+
+    /// The integer an operation computes if both operands are integers and it fits, otherwise its residue.
+    fn integer(self, rhs: Self, operation: fn(BigInt, BigInt) -> Result<BigInt, NumberError>, reduced: fn(BigInt, BigInt) -> BigInt) -> Result<Self, NumberError> {
+        if self.integer && rhs.integer {
+            if let Ok(value) = operation(self.value.clone(), rhs.value.clone()) {
+                return Ok(Self::new(value));
+            }
+        }
+        Self::reduce(reduced(self.value, rhs.value))
+    }
+
+    /// The residue together with the modulus.
+    fn parts(&self) -> Result<(BigInt, BigInt), NumberError> {
+        let modulus = current()?;
+        Ok((self.value.mod_floor(&modulus), modulus))
+    }
+
+    /// The `x` with `self * x ≡ 1`, which exists if `self` and the modulus are coprime.
+    fn inverse(&self) -> Result<BigInt, NumberError> {
+        let (residue, modulus) = self.parts()?;
+        if residue.is_zero() && !modulus.is_one() {
+            return Err(NumberError::DivisionByZero);
+        }
+        let gcd = residue.extended_gcd(&modulus);
+        if !gcd.gcd.is_one() {
+            return Err(NumberError::Domain(format!("{self} has no inverse modulo {modulus}, they share the factor {}", gcd.gcd)));
+        }
+        Ok(gcd.x.mod_floor(&modulus))
+    }
+}
+
+impl Display for Modular {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.residue().unwrap_or_else(|| self.value.clone()))
+    }
+}
+
+impl PartialEq for Modular {
+    fn eq(&self, other: &Self) -> bool {
+        self.residue() == other.residue()
+    }
+}
+
+impl Number for Modular {
+    /// Only integers, of any size.
+    fn from_literal(literal: &Literal) -> Option<Self> {
+        BigInt::from_literal(literal).map(Self::new)
+    }
+
+    fn try_add(self, rhs: Self) -> Result<Self, NumberError> {
+        self.integer(rhs, BigInt::try_add, |a, b| a + b)
+    }
+
+    fn try_sub(self, rhs: Self) -> Result<Self, NumberError> {
+        self.integer(rhs, BigInt::try_sub, |a, b| a - b)
+    }
+
+    fn try_mul(self, rhs: Self) -> Result<Self, NumberError> {
+        self.integer(rhs, BigInt::try_mul, |a, b| a * b)
+    }
+
+    fn try_div(self, rhs: Self) -> Result<Self, NumberError> {
+        Self::reduce(self.value * rhs.inverse()?)
+    }
+
+    /// The remainder of the integers, or of the residues like `%` of the unsigned integers if either is only
+    /// known by its residue.
+    fn try_rem(self, rhs: Self) -> Result<Self, NumberError> {
+        if self.integer && rhs.integer {
+            return self.value.try_rem(rhs.value).map(Self::new);
+        }
+        let (divisor, _) = rhs.parts()?;
+        if divisor.is_zero() {
+            return Err(NumberError::DivisionByZero);
+        }
+        Self::reduce(self.parts()?.0 % divisor)
+    }
+
+    /// Negative exponents raise the inverse.
+    fn try_pow(self, rhs: Self) -> Result<Self, NumberError> {
+        let modulus = current()?;
+        if !rhs.integer {
+            return Err(NumberError::Domain(format!("the exponent {rhs} is only known modulo {modulus}")));
+        }
+        if self.integer && !rhs.value.is_negative() {
+            if let Ok(value) = self.value.clone().try_pow(rhs.value.clone()) {
+                return Ok(Self::new(value));
+            }
+        }
+        let base = if rhs.value.is_negative() { self.inverse()? } else { self.value };
+        Self::reduce(base.modpow(&Signed::abs(&rhs.value), &modulus))
+    }
+
+    fn try_neg(self) -> Result<Self, NumberError> {
+        Ok(Self {
+            value: -self.value,
+            integer: self.integer,
+        })
+    }
+
+    fn to_float(&self) -> Option<f64> {
+        ToPrimitive::to_f64(&self.residue()?)
+    }
+
+    fn from_bool(value: bool) -> Self {
+        Self::new(u8::from(value))
+    }
+
+    fn try_cmp(&self, other: &Self) -> Result<Option<Ordering>, NumberError> {
+        Ok(Some(self.parts()?.0.cmp(&other.parts()?.0)))
+    }
+}
+
+/// Only `min` and `max` of the residues apply to residue classes.
+impl Elementary for Modular {
+    fn minimum(self, other: Self) -> Result<Self, NumberError> {
+        Ok(if self.try_cmp(&other)? == Some(Ordering::Greater) { other } else { self })
+    }
+
+    fn maximum(self, other: Self) -> Result<Self, NumberError> {
+        Ok(if self.try_cmp(&other)? == Some(Ordering::Less) { other } else { self })
+    }
+}
@@ -0,0 +1,512 @@
+use crate::parse::Literal;
+use num::traits::{Float, PrimInt};
+use num::complex::Complex64;
+use num::BigRational;
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::num::{Saturating, Wrapping};
+
+/// An error raised by a [`Number`] operation, the evaluator attaches the position it occurred at.
+#[derive(Debug, PartialEq)]
+pub enum NumberError {
+    /// The operation is not defined for this type.
+    Unsupported(&'static str),
+    /// The arguments are outside the domain of the operation.
+    Domain(String),
+    /// The result does not fit into the type.
+    Overflow,
+    /// The divisor of `/` or `%` is zero.
+    DivisionByZero,
+}
+
+impl Display for NumberError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NumberError::Unsupported(operation) => write!(f, "{operation} is not supported"),
+            NumberError::Domain(message) => write!(f, "{message}"),
+            NumberError::Overflow => write!(f, "arithmetic overflow"),
+            NumberError::DivisionByZero => write!(f, "division by zero"),
+        }
+    }
+}
+
+macro_rules! unsupported {
+    ($($name:ident: $operation:literal),*) => {$(
+        fn $name(self, _rhs: Self) -> Result<Self, NumberError> {
+            Err(NumberError::Unsupported($operation))
+        }
+    )*};
+}
+
+/// The operations the evaluator performs on values, each type decides how it detects overflow.
+///
+/// The bitwise operators default to [`NumberError::Unsupported`] and are only implemented by the integer types.
+pub trait Number: Sized {
+    /// Converts a literal from the input, `None` if it cannot be represented by this type.
+    fn from_literal(literal: &Literal) -> Option<Self>;
+
+    /// Converts the negation of a literal whose positive value does not fit, like `-128` for `i8`.
+    fn from_negated_literal(_literal: &Literal) -> Option<Self> {
+        None
+    }
+
+    fn try_add(self, rhs: Self) -> Result<Self, NumberError>;
+
+    fn try_sub(self, rhs: Self) -> Result<Self, NumberError>;
+
+    fn try_mul(self, rhs: Self) -> Result<Self, NumberError>;
+
+    /// Divides, fails with [`NumberError::DivisionByZero`] unless the type can represent the result,
+    /// like the floating point types returning infinity or `NaN`.
+    fn try_div(self, rhs: Self) -> Result<Self, NumberError>;
+
+    fn try_rem(self, rhs: Self) -> Result<Self, NumberError>;
+
+    fn try_pow(self, rhs: Self) -> Result<Self, NumberError>;
+
+    unsupported!(try_bitand: "bitwise and", try_bitor: "bitwise or", try_bitxor: "xor", try_shl: "shift left", try_shr: "shift right");
+
+    /// The interval `[self, hi]`, only supported by interval types.
+    fn try_interval(self, _hi: Self) -> Result<Self, NumberError> {
+        Err(NumberError::Unsupported("interval"))
+    }
+
+    /// The measurement `self ± uncertainty`, only supported by types that propagate uncertainties.
+    fn try_uncertain(self, _uncertainty: Self) -> Result<Self, NumberError> {
+        Err(NumberError::Unsupported("uncertainty"))
+    }
+
+    /// The bitwise complement `~`.
+    fn try_bitnot(self) -> Result<Self, NumberError> {
+        Err(NumberError::Unsupported("bitwise not"))
+    }
+
+    /// The raw bits of the value together with the bit width of the type, `None` for types without a fixed width.
+    fn to_bits(&self) -> Option<(u128, u32)> {
+        None
+    }
+
+    /// The value as an exact fraction, `None` for types that do not store one.
+    fn to_ratio(&self) -> Option<BigRational> {
+        None
+    }
+
+    /// The value as a complex number, `None` for real types.
+    fn to_complex(&self) -> Option<Complex64> {
+        None
+    }
+
+    /// The closest real number, `None` for values that are not one.
+    fn to_float(&self) -> Option<f64> {
+        None
+    }
+
+    /// The value `i` and `j` are read as, `None` for real types where they name variables.
+    fn imaginary_unit() -> Option<Self> {
+        None
+    }
+
+    /// Negates the value, fails for types that cannot represent negative numbers.
+    fn try_neg(self) -> Result<Self, NumberError>;
+
+    /// The value comparisons and logical operators produce, `1` for true and `0` for false.
+    fn from_bool(value: bool) -> Self;
+
+    /// Orders two values, `None` if they are unordered like `NaN`.
+    fn try_cmp(&self, other: &Self) -> Result<Option<Ordering>, NumberError>;
+
+    /// Whether two values are numerically equal, regardless of their representation.
+    fn try_eq(&self, other: &Self) -> Result<bool, NumberError> {
+        Ok(self.try_cmp(other)? == Some(Ordering::Equal))
+    }
+
+    /// Any value other than zero is true.
+    fn is_truthy(&self) -> Result<bool, NumberError> {
+        Ok(!self.try_eq(&Self::from_bool(false))?)
+    }
+}
+
+/// Shifts out of the type's width result in zero, like shifting in one bit at a time would.
+fn shift_left<T: PrimInt>(value: T, amount: T) -> T {
+    match amount.to_u32() {
+        Some(amount) if amount < T::zero().count_zeros() => value << amount as usize,
+        _ => T::zero(),
+    }
+}
+
+/// Shifts left, `None` if any set bit or, for signed types, the sign is lost.
+fn checked_shift_left<T: PrimInt>(value: T, amount: T) -> Option<T> {
+    let shifted = shift_left(value, amount);
+    (shift_right(shifted, amount) == value).then_some(shifted)
+}
+
+fn shift_right<T: PrimInt>(value: T, amount: T) -> T {
+    match amount.to_u32() {
+        Some(amount) if amount < T::zero().count_zeros() => value >> amount as usize,
+        _ => T::zero(),
+    }
+}
+
+/// The bound of the type on the side of the sign of `value`.
+fn saturate<T: PrimInt>(value: T) -> T {
+    if value < T::zero() {
+        T::min_value()
+    } else {
+        T::max_value()
+    }
+}
+
+/// Adapts an exponent of the integer types to the `u32` their powers take, negative exponents have no integer result.
+///
+/// The powers of 0 and ±1 are known for every exponent, so theirs is replaced by a small one with the same result.
+fn exponent<T: PrimInt + Display>(base: T, exponent: T) -> Result<u32, NumberError> {
+    let odd = exponent % (T::one() + T::one()) != T::zero();
+    match base.to_i8() {
+        Some(1) => return Ok(0),
+        Some(-1) => return Ok(u32::from(odd)),
+        Some(0) if exponent > T::zero() => return Ok(1),
+        _ => {}
+    }
+    if exponent < T::zero() {
+        return Err(NumberError::Domain(format!("{base} cannot be raised to the negative power {exponent}, the result would not be an integer")));
+    }
+    exponent.to_u32().ok_or(NumberError::Overflow)
+}
+
+/// The plain integer types use checked arithmetic and report [`NumberError::Overflow`],
+/// each is listed with the unsigned type of the same width its bits are shown as.
+macro_rules! impl_integer {
+    ($($t:ty: $bits:ty),*) => {$(
+        impl Number for $t {
+            fn from_literal(literal: &Literal) -> Option<Self> {
+                literal.to_integer().and_then(|v| <$t>::try_from(v).ok())
+            }
+
+            fn from_negated_literal(literal: &Literal) -> Option<Self> {
+                literal.to_integer().and_then(|v| 0_i128.checked_sub_unsigned(v)).and_then(|v| <$t>::try_from(v).ok())
+            }
+
+            fn try_add(self, rhs: Self) -> Result<Self, NumberError> {
+                self.checked_add(rhs).ok_or(NumberError::Overflow)
+            }
+
+            fn try_sub(self, rhs: Self) -> Result<Self, NumberError> {
+                self.checked_sub(rhs).ok_or(NumberError::Overflow)
+            }
+
+            fn try_mul(self, rhs: Self) -> Result<Self, NumberError> {
+                self.checked_mul(rhs).ok_or(NumberError::Overflow)
+            }
+
+            /// Besides a zero divisor, only `MIN / -1` fails, its result does not fit.
+            fn try_div(self, rhs: Self) -> Result<Self, NumberError> {
+                if rhs == 0 {
+                    return Err(NumberError::DivisionByZero);
+                }
+                self.checked_div(rhs).ok_or(NumberError::Overflow)
+            }
+
+            fn try_rem(self, rhs: Self) -> Result<Self, NumberError> {
+                if rhs == 0 {
+                    return Err(NumberError::DivisionByZero);
+                }
+                // `MIN % -1` overflows while computing the quotient, the remainder is zero
+                Ok(self.checked_rem(rhs).unwrap_or(0))
+            }
+
+            fn try_pow(self, rhs: Self) -> Result<Self, NumberError> {
+                self.checked_pow(exponent(self, rhs)?).ok_or(NumberError::Overflow)
+            }
+
+            fn try_bitand(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(self & rhs)
+            }
+
+            fn try_bitor(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(self | rhs)
+            }
+
+            fn try_bitxor(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(self ^ rhs)
+            }
+
+            fn try_shl(self, rhs: Self) -> Result<Self, NumberError> {
+                checked_shift_left(self, rhs).ok_or(NumberError::Overflow)
+            }
+
+            fn try_shr(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(shift_right(self, rhs))
+            }
+
+            fn try_bitnot(self) -> Result<Self, NumberError> {
+                Ok(!self)
+            }
+
+            fn to_bits(&self) -> Option<(u128, u32)> {
+                Some((*self as $bits as u128, <$t>::BITS))
+            }
+
+            fn to_float(&self) -> Option<f64> {
+                Some(*self as f64)
+            }
+
+            fn try_neg(self) -> Result<Self, NumberError> {
+                match <$t>::MIN {
+                    0 => Err(NumberError::Unsupported("negation")),
+                    _ => self.checked_neg().ok_or(NumberError::Overflow),
+                }
+            }
+
+            fn from_bool(value: bool) -> Self {
+                value.into()
+            }
+
+            fn try_cmp(&self, other: &Self) -> Result<Option<Ordering>, NumberError> {
+                Ok(Some(self.cmp(other)))
+            }
+        }
+    )*};
+}
+
+/// Integers under [`Wrapping`] arithmetic drop the bits that do not fit, so `255 + 1` is `0` for `u8`
+/// and negation takes the two's complement.
+macro_rules! impl_wrapping {
+    ($($t:ty),*) => {$(
+        impl Number for Wrapping<$t> {
+            fn from_literal(literal: &Literal) -> Option<Self> {
+                <$t>::from_literal(literal).map(Wrapping)
+            }
+
+            fn from_negated_literal(literal: &Literal) -> Option<Self> {
+                <$t>::from_negated_literal(literal).map(Wrapping)
+            }
+
+            fn try_add(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(self + rhs)
+            }
+
+            fn try_sub(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(self - rhs)
+            }
+
+            fn try_mul(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(self * rhs)
+            }
+
+            fn try_div(self, rhs: Self) -> Result<Self, NumberError> {
+                if rhs.0 == 0 {
+                    return Err(NumberError::DivisionByZero);
+                }
+                Ok(Wrapping(self.0.wrapping_div(rhs.0)))
+            }
+
+            fn try_rem(self, rhs: Self) -> Result<Self, NumberError> {
+                if rhs.0 == 0 {
+                    return Err(NumberError::DivisionByZero);
+                }
+                Ok(Wrapping(self.0.wrapping_rem(rhs.0)))
+            }
+
+            fn try_pow(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(Wrapping(self.0.wrapping_pow(exponent(self.0, rhs.0)?)))
+            }
+
+            fn try_bitand(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(self & rhs)
+            }
+
+            fn try_bitor(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(self | rhs)
+            }
+
+            fn try_bitxor(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(self ^ rhs)
+            }
+
+            fn try_shl(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(Wrapping(shift_left(self.0, rhs.0)))
+            }
+
+            fn try_shr(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(Wrapping(shift_right(self.0, rhs.0)))
+            }
+
+            fn try_bitnot(self) -> Result<Self, NumberError> {
+                Ok(!self)
+            }
+
+            fn to_bits(&self) -> Option<(u128, u32)> {
+                self.0.to_bits()
+            }
+
+            fn to_float(&self) -> Option<f64> {
+                self.0.to_float()
+            }
+
+            fn try_neg(self) -> Result<Self, NumberError> {
+                Ok(-self)
+            }
+
+            fn from_bool(value: bool) -> Self {
+                Wrapping(value.into())
+            }
+
+            fn try_cmp(&self, other: &Self) -> Result<Option<Ordering>, NumberError> {
+                Ok(Some(self.cmp(other)))
+            }
+        }
+    )*};
+}
+
+/// Integers under [`Saturating`] arithmetic clamp to the bounds of the type, so `255 + 1` is `255` for `u8`,
+/// `1 - 2` and any unsigned negation are `0` and a left shift losing set bits gives the bound of its sign.
+macro_rules! impl_saturating {
+    ($($t:ty),*) => {$(
+        impl Number for Saturating<$t> {
+            fn from_literal(literal: &Literal) -> Option<Self> {
+                <$t>::from_literal(literal).map(Saturating)
+            }
+
+            fn from_negated_literal(literal: &Literal) -> Option<Self> {
+                <$t>::from_negated_literal(literal).map(Saturating)
+            }
+
+            fn try_add(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(self + rhs)
+            }
+
+            fn try_sub(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(self - rhs)
+            }
+
+            fn try_mul(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(self * rhs)
+            }
+
+            fn try_div(self, rhs: Self) -> Result<Self, NumberError> {
+                if rhs.0 == 0 {
+                    return Err(NumberError::DivisionByZero);
+                }
+                Ok(Saturating(self.0.saturating_div(rhs.0)))
+            }
+
+            /// The remainder always fits, `MIN % -1` is `0`.
+            fn try_rem(self, rhs: Self) -> Result<Self, NumberError> {
+                if rhs.0 == 0 {
+                    return Err(NumberError::DivisionByZero);
+                }
+                Ok(Saturating(self.0.checked_rem(rhs.0).unwrap_or(0)))
+            }
+
+            /// Exponents beyond `u32` saturate like the largest exponent of the same parity.
+            fn try_pow(self, rhs: Self) -> Result<Self, NumberError> {
+                match exponent(self.0, rhs.0) {
+                    Err(NumberError::Overflow) => Ok(Saturating(self.0.saturating_pow(u32::MAX - u32::from(rhs.0 % 2 == 0)))),
+                    exponent => Ok(Saturating(self.0.saturating_pow(exponent?))),
+                }
+            }
+
+            fn try_bitand(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(self & rhs)
+            }
+
+            fn try_bitor(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(self | rhs)
+            }
+
+            fn try_bitxor(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(self ^ rhs)
+            }
+
+            fn try_shl(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(Saturating(checked_shift_left(self.0, rhs.0).unwrap_or_else(|| saturate(self.0))))
+            }
+
+            fn try_shr(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(Saturating(shift_right(self.0, rhs.0)))
+            }
+
+            fn try_bitnot(self) -> Result<Self, NumberError> {
+                Ok(!self)
+            }
+
+            fn to_bits(&self) -> Option<(u128, u32)> {
+                self.0.to_bits()
+            }
+
+            fn to_float(&self) -> Option<f64> {
+                self.0.to_float()
+            }
+
+            fn try_neg(self) -> Result<Self, NumberError> {
+                Ok(Saturating(0) - self)
+            }
+
+            fn from_bool(value: bool) -> Self {
+                Saturating(value.into())
+            }
+
+            fn try_cmp(&self, other: &Self) -> Result<Option<Ordering>, NumberError> {
+                Ok(Some(self.cmp(other)))
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_float {
+    ($($t:ty),*) => {$(
+        impl Number for $t {
+            fn from_literal(literal: &Literal) -> Option<Self> {
+                match literal.radix {
+                    10 => literal.to_string().parse().ok(),
+                    _ => literal.to_integer().map(|v| v as $t),
+                }
+            }
+
+            fn try_add(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(self + rhs)
+            }
+
+            fn try_sub(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(self - rhs)
+            }
+
+            fn try_mul(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(self * rhs)
+            }
+
+            fn try_div(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(self / rhs)
+            }
+
+            fn try_rem(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(self % rhs)
+            }
+
+            fn try_pow(self, rhs: Self) -> Result<Self, NumberError> {
+                Ok(Float::powf(self, rhs))
+            }
+
+            fn try_neg(self) -> Result<Self, NumberError> {
+                Ok(-self)
+            }
+
+            fn from_bool(value: bool) -> Self {
+                value.into()
+            }
+
+            fn try_cmp(&self, other: &Self) -> Result<Option<Ordering>, NumberError> {
+                Ok(self.partial_cmp(other))
+            }
+
+            fn to_float(&self) -> Option<f64> {
+                Some(*self as f64)
+            }
+        }
+    )*};
+}
+
+impl_integer!(u8: u8, u16: u16, u32: u32, u64: u64, u128: u128, usize: usize, i8: u8, i16: u16, i32: u32, i64: u64, i128: u128);
+impl_wrapping!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128);
+impl_saturating!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128);
+impl_float!(f32, f64);
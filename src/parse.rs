@@ -1,48 +1,162 @@
+use crate::function::Functions;
+use crate::number::Number;
 use log::trace;
 use std::any::type_name;
 use std::fmt::{Debug, Display, Formatter};
-use std::iter::{Enumerate, Peekable};
-use std::str::{Chars, FromStr};
+use std::iter::Peekable;
+use std::str::{CharIndices, FromStr};
 
-use crate::parse::Expr::{Add, Div, Mul, Pow, Rem, Sub};
+/// A byte range `start..end` into the parsed input.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// Creates the smallest span covering both `self` and `other`.
+    pub fn to(self, other: Span) -> Self {
+        Self::new(self.start.min(other.start), self.end.max(other.end))
+    }
+}
 
 #[derive(Debug, PartialEq)]
 pub enum ParseError {
-    ValueError(String, String),
-    UnexpectedTokenError(usize, char),
-    EmptyError,
+    ValueError(String, String, Span),
+    LiteralError(String, Span),
+    UnexpectedTokenError(String, Span),
+    UnexpectedEndError(Span),
+    EmptyError(Span),
+}
+
+impl ParseError {
+    pub fn span(&self) -> Span {
+        match self {
+            ParseError::ValueError(_, _, span) | ParseError::LiteralError(_, span) | ParseError::UnexpectedTokenError(_, span) | ParseError::UnexpectedEndError(span) | ParseError::EmptyError(span) => *span,
+        }
+    }
 }
 
 impl Display for ParseError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            ParseError::ValueError(input, type_name) => {
-                write!(f, "could not parse {input} to {type_name}")
+            ParseError::ValueError(input, type_name, _) => {
+                write!(f, "{input} cannot be represented as {type_name}")
             }
-            ParseError::UnexpectedTokenError(pos, char) => {
-                write!(f, "found unexpected token {char} at position {pos}")
+            ParseError::LiteralError(input, _) => write!(f, "malformed number {input}"),
+            ParseError::UnexpectedTokenError(token, span) => {
+                write!(f, "found unexpected token {token} at position {}", span.start)
             }
-            ParseError::EmptyError => write!(f, "empty input found while parsing"),
+            ParseError::UnexpectedEndError(_) => write!(f, "unexpected end of input while parsing"),
+            ParseError::EmptyError(_) => write!(f, "empty input found while parsing"),
         }
     }
 }
 
 #[derive(Debug, PartialEq, Clone)]
-pub enum Token<T> {
+pub struct Token<T> {
+    pub kind: TokenKind<T>,
+    pub span: Span,
+}
+
+impl<T> Token<T> {
+    pub fn new(kind: TokenKind<T>, span: Span) -> Self {
+        Self { kind, span }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum TokenKind<T> {
     Value(T),
     Variable(String),
+    Function(String),
     OpeningBrackets,
     ClosingBrackets,
+    OpeningSquareBrackets,
+    ClosingSquareBrackets,
     AddSymbol,
     SubSymbol,
     MulSymbol,
     DivSymbol,
     PowSymbol,
+    PlusMinusSymbol,
     RemSymbol,
+    EqSymbol,
+    NeSymbol,
+    LtSymbol,
+    LeSymbol,
+    GtSymbol,
+    GeSymbol,
+    AndSymbol,
+    OrSymbol,
+    NotSymbol,
+    BitAndSymbol,
+    BitOrSymbol,
+    XorSymbol,
+    BitNotSymbol,
+    ShlSymbol,
+    ShrSymbol,
+    QuestionSymbol,
+    ColonSymbol,
+    Comma,
+}
+
+impl<T: Debug> TokenKind<T> {
+    fn symbol(&self) -> String {
+        match self {
+            TokenKind::Value(v) => format!("{v:?}"),
+            TokenKind::Variable(v) | TokenKind::Function(v) => v.clone(),
+            TokenKind::OpeningBrackets => "(".into(),
+            TokenKind::ClosingBrackets => ")".into(),
+            TokenKind::OpeningSquareBrackets => "[".into(),
+            TokenKind::ClosingSquareBrackets => "]".into(),
+            TokenKind::AddSymbol => "+".into(),
+            TokenKind::SubSymbol => "-".into(),
+            TokenKind::MulSymbol => "*".into(),
+            TokenKind::DivSymbol => "/".into(),
+            TokenKind::PowSymbol => "^".into(),
+            TokenKind::PlusMinusSymbol => "±".into(),
+            TokenKind::RemSymbol => "%".into(),
+            TokenKind::EqSymbol => "==".into(),
+            TokenKind::NeSymbol => "!=".into(),
+            TokenKind::LtSymbol => "<".into(),
+            TokenKind::LeSymbol => "<=".into(),
+            TokenKind::GtSymbol => ">".into(),
+            TokenKind::GeSymbol => ">=".into(),
+            TokenKind::AndSymbol => "&&".into(),
+            TokenKind::OrSymbol => "||".into(),
+            TokenKind::NotSymbol => "!".into(),
+            TokenKind::BitAndSymbol => "&".into(),
+            TokenKind::BitOrSymbol => "|".into(),
+            TokenKind::XorSymbol => "xor".into(),
+            TokenKind::BitNotSymbol => "~".into(),
+            TokenKind::ShlSymbol => "<<".into(),
+            TokenKind::ShrSymbol => ">>".into(),
+            TokenKind::QuestionSymbol => "?".into(),
+            TokenKind::ColonSymbol => ":".into(),
+            TokenKind::Comma => ",".into(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Expr<T> {
+    pub kind: ExprKind<T>,
+    pub span: Span,
+}
+
+impl<T> Expr<T> {
+    pub fn new(kind: ExprKind<T>, span: Span) -> Self {
+        Self { kind, span }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
-pub enum Expr<T> {
+pub enum ExprKind<T> {
     Value(T),
     Variable(String),
     Add(Box<Expr<T>>, Box<Expr<T>>),
@@ -51,174 +165,614 @@ pub enum Expr<T> {
     Div(Box<Expr<T>>, Box<Expr<T>>),
     Pow(Box<Expr<T>>, Box<Expr<T>>),
     Rem(Box<Expr<T>>, Box<Expr<T>>),
+    Neg(Box<Expr<T>>),
+    Call(String, Vec<Expr<T>>),
+    Eq(Box<Expr<T>>, Box<Expr<T>>),
+    Ne(Box<Expr<T>>, Box<Expr<T>>),
+    Lt(Box<Expr<T>>, Box<Expr<T>>),
+    Le(Box<Expr<T>>, Box<Expr<T>>),
+    Gt(Box<Expr<T>>, Box<Expr<T>>),
+    Ge(Box<Expr<T>>, Box<Expr<T>>),
+    And(Box<Expr<T>>, Box<Expr<T>>),
+    Or(Box<Expr<T>>, Box<Expr<T>>),
+    Not(Box<Expr<T>>),
+    BitAnd(Box<Expr<T>>, Box<Expr<T>>),
+    BitOr(Box<Expr<T>>, Box<Expr<T>>),
+    BitXor(Box<Expr<T>>, Box<Expr<T>>),
+    BitNot(Box<Expr<T>>),
+    Shl(Box<Expr<T>>, Box<Expr<T>>),
+    Shr(Box<Expr<T>>, Box<Expr<T>>),
+    /// `condition ? then : otherwise`
+    Conditional(Box<Expr<T>>, Box<Expr<T>>, Box<Expr<T>>),
+    /// `[lo, hi]`
+    Interval(Box<Expr<T>>, Box<Expr<T>>),
+    /// `value ± uncertainty`
+    Uncertain(Box<Expr<T>>, Box<Expr<T>>),
+}
+
+/// A numeric literal split into its digits, separators and prefixes are already removed.
+///
+/// The value is `integer.fraction * 10^exponent`, literals with a radix other than 10 only have integer digits.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Literal {
+    pub radix: u32,
+    pub integer: String,
+    pub fraction: String,
+    pub exponent: i32,
+}
+
+impl Literal {
+    /// The exact integer value, `None` if the literal has a fractional part or does not fit into a `u128`.
+    pub fn to_integer(&self) -> Option<u128> {
+        if self.radix != 10 {
+            return u128::from_str_radix(&self.integer, self.radix).ok();
+        }
+        let (digits, exponent) = self.to_digits();
+        if exponent >= 0 {
+            return digits.parse::<u128>().ok()?.checked_mul(10_u128.checked_pow(exponent as u32)?);
+        }
+        let split = digits.len().saturating_sub(exponent.unsigned_abs() as usize);
+        if digits[split..].chars().any(|c| c != '0') {
+            return None;
+        }
+        match split {
+            0 => Some(0),
+            _ => digits[..split].parse().ok(),
+        }
+    }
+
+    /// All digits of a decimal literal together with the power of ten they are scaled by.
+    pub fn to_digits(&self) -> (String, i64) {
+        (format!("{}{}", self.integer, self.fraction), self.exponent as i64 - self.fraction.len() as i64)
+    }
+}
+
+/// Reads a whole string as a literal of an expression, which has no sign.
+impl FromStr for Literal {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut iter = s.char_indices().peekable();
+        let literal = match iter.next() {
+            Some((i, c @ ('0'..='9' | '.'))) => read_literal(c, i, &mut iter)?.0,
+            Some((i, c)) => return Err(ParseError::UnexpectedTokenError(c.into(), Span::new(i, i + c.len_utf8()))),
+            None => return Err(ParseError::LiteralError(String::new(), Span::new(0, 0))),
+        };
+        match iter.next() {
+            Some((i, c)) => Err(ParseError::UnexpectedTokenError(c.into(), Span::new(i, i + c.len_utf8()))),
+            None => Ok(literal),
+        }
+    }
+}
+
+impl Display for Literal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.radix {
+            16 => write!(f, "0x{}", self.integer),
+            8 => write!(f, "0o{}", self.integer),
+            2 => write!(f, "0b{}", self.integer),
+            _ => {
+                let integer = if self.integer.is_empty() { "0" } else { &self.integer };
+                let fraction = if self.fraction.is_empty() { "0" } else { &self.fraction };
+                write!(f, "{integer}.{fraction}e{}", self.exponent)
+            }
+        }
+    }
+}
+
+/// How letters in the input are grouped into names.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Identifiers {
+    /// Every letter is a variable of its own, so `2ab` is `2*a*b`, longer names have to be quoted like `"rate"`.
+    #[default]
+    Letters,
+    /// `[A-Za-z_][A-Za-z0-9_]*` is a single name, so `rate*time` multiplies two variables.
+    Words,
 }
 
-pub fn tokenize<T: Debug + FromStr>(input: String) -> Result<Vec<Token<T>>, ParseError> {
-    let mut tokens = Vec::new();
-    let mut iter = input.chars().enumerate().peekable();
+/// Options controlling how the input is tokenized.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Syntax {
+    pub identifiers: Identifiers,
+    /// Whether adjacent operands such as `2a` or `3(1+2)` are multiplied.
+    pub implicit_multiplication: bool,
+}
+
+impl Default for Syntax {
+    fn default() -> Self {
+        Self {
+            identifiers: Identifiers::Letters,
+            implicit_multiplication: true,
+        }
+    }
+}
+
+type Chars<'a> = Peekable<CharIndices<'a>>;
+
+/// Splits the input into tokens, in [`Identifiers::Letters`] mode only the names registered in `functions` are called.
+pub fn tokenize<T: Number + Debug>(input: String, syntax: &Syntax, functions: &Functions<T>) -> Result<Vec<Token<T>>, ParseError> {
+    let mut tokens: Vec<Token<T>> = Vec::new();
+    let mut iter = input.char_indices().peekable();
     while let Some((i, c)) = iter.next() {
+        let span = Span::new(i, i + c.len_utf8());
+        let implicit = syntax.implicit_multiplication && matches!(tokens.last().map(|t| &t.kind), Some(TokenKind::Value(_)) | Some(TokenKind::Variable(_)));
         match c {
+            '+' if iter.clone().next().map(|(_, c)| c) == Some('/') && iter.clone().nth(1).map(|(_, c)| c) == Some('-') => {
+                iter.nth(1);
+                tokens.push(Token::new(TokenKind::PlusMinusSymbol, Span::new(i, i + 3)));
+            }
+            '±' => tokens.push(Token::new(TokenKind::PlusMinusSymbol, span)),
             '+' | '-' | '*' | '/' | '^' | '%' => {
-                if tokens.is_empty() {
-                    tokens.push(parse_num(c, &mut iter)?);
-                } else if !tokens.is_empty() && matches!(tokens.last(), Some(Token::OpeningBrackets)) {
-                    tokens.pop();
-                    tokens.push(parse_num(c, &mut iter)?);
-                    iter.next();
-                } else if !tokens.is_empty()
-                    && !matches!(tokens.last(), Some(Token::Value(_)))
-                    && !matches!(tokens.last(), Some(Token::Variable(_)))
-                    && !matches!(tokens.last(), Some(Token::ClosingBrackets))
-                {
-                    return Err(ParseError::UnexpectedTokenError(i, c));
-                } else {
-                    match c {
-                        '+' => tokens.push(Token::AddSymbol),
-                        '-' => tokens.push(Token::SubSymbol),
-                        '*' => tokens.push(Token::MulSymbol),
-                        '/' => tokens.push(Token::DivSymbol),
-                        '^' => tokens.push(Token::PowSymbol),
-                        '%' => tokens.push(Token::RemSymbol),
-                        _ => unreachable!(),
-                    }
-                }
+                let kind = match c {
+                    '+' => TokenKind::AddSymbol,
+                    '-' => TokenKind::SubSymbol,
+                    '*' => TokenKind::MulSymbol,
+                    '/' => TokenKind::DivSymbol,
+                    '^' => TokenKind::PowSymbol,
+                    '%' => TokenKind::RemSymbol,
+                    _ => unreachable!(),
+                };
+                tokens.push(Token::new(kind, span));
+            }
+            '=' | '!' | '<' | '>' | '&' | '|' => {
+                let followed_by = |next: char, iter: &mut Chars| iter.next_if(|(_, c)| *c == next).is_some();
+                let kind = match c {
+                    '=' if followed_by('=', &mut iter) => TokenKind::EqSymbol,
+                    '!' if followed_by('=', &mut iter) => TokenKind::NeSymbol,
+                    '!' => TokenKind::NotSymbol,
+                    '<' if followed_by('<', &mut iter) => TokenKind::ShlSymbol,
+                    '<' if followed_by('=', &mut iter) => TokenKind::LeSymbol,
+                    '<' => TokenKind::LtSymbol,
+                    '>' if followed_by('>', &mut iter) => TokenKind::ShrSymbol,
+                    '>' if followed_by('=', &mut iter) => TokenKind::GeSymbol,
+                    '>' => TokenKind::GtSymbol,
+                    '&' if followed_by('&', &mut iter) => TokenKind::AndSymbol,
+                    '&' => TokenKind::BitAndSymbol,
+                    '|' if followed_by('|', &mut iter) => TokenKind::OrSymbol,
+                    '|' => TokenKind::BitOrSymbol,
+                    _ => return Err(ParseError::UnexpectedTokenError(c.into(), span)),
+                };
+                let end = iter.peek().map_or(input.len(), |(j, _)| *j);
+                tokens.push(Token::new(kind, Span::new(i, end)));
             }
+            '~' => tokens.push(Token::new(TokenKind::BitNotSymbol, span)),
+            '?' => tokens.push(Token::new(TokenKind::QuestionSymbol, span)),
+            ':' => tokens.push(Token::new(TokenKind::ColonSymbol, span)),
             '(' => {
-                if !tokens.is_empty() && (matches!(tokens.last(), Some(Token::Value(_))) || matches!(tokens.last(), Some(Token::Variable(_)))) {
-                    tokens.push(Token::MulSymbol);
+                if implicit {
+                    tokens.push(Token::new(TokenKind::MulSymbol, Span::new(i, i)));
+                }
+                tokens.push(Token::new(TokenKind::OpeningBrackets, span));
+            }
+            ')' => tokens.push(Token::new(TokenKind::ClosingBrackets, span)),
+            '[' => {
+                if implicit {
+                    tokens.push(Token::new(TokenKind::MulSymbol, Span::new(i, i)));
                 }
-                tokens.push(Token::OpeningBrackets);
+                tokens.push(Token::new(TokenKind::OpeningSquareBrackets, span));
             }
-            ')' => tokens.push(Token::ClosingBrackets),
-            '0'..='9' | '.' => tokens.push(parse_num(c, &mut iter)?),
-            'a'..='z' | 'A'..='Z' | '"' => {
-                if !tokens.is_empty() && (matches!(tokens.last(), Some(Token::Value(_))) || matches!(tokens.last(), Some(Token::Variable(_)))) {
-                    tokens.push(Token::MulSymbol);
+            ']' => tokens.push(Token::new(TokenKind::ClosingSquareBrackets, span)),
+            ',' => tokens.push(Token::new(TokenKind::Comma, span)),
+            '0'..='9' | '.' => {
+                let token = parse_num(c, i, &mut iter, prefix_minus(&tokens))?;
+                if token.span.start < i {
+                    tokens.pop();
+                }
+                tokens.push(token);
+            }
+            'a'..='z' | 'A'..='Z' | '_' | '"' => {
+                if let Some(keyword) = parse_keyword(c, i, &mut iter) {
+                    tokens.push(keyword);
+                    continue;
+                }
+                if implicit {
+                    tokens.push(Token::new(TokenKind::MulSymbol, Span::new(i, i)));
                 }
-                tokens.push(parse_variable(c, &mut iter)?);
+                let mut token = match syntax.identifiers {
+                    Identifiers::Words if c != '"' => parse_word(c, i, &mut iter),
+                    _ => match parse_function(c, i, &mut iter, functions) {
+                        Some(function) => function,
+                        None => parse_variable(c, i, &mut iter)?,
+                    },
+                };
+                if let (TokenKind::Variable(name), Some(unit)) = (&token.kind, T::imaginary_unit()) {
+                    if c != '"' && (name == "i" || name == "j") {
+                        token.kind = TokenKind::Value(unit);
+                    }
+                }
+                tokens.push(token);
             }
             ' ' => continue,
-            _ => return Err(ParseError::UnexpectedTokenError(i, c)),
+            _ => return Err(ParseError::UnexpectedTokenError(c.into(), span)),
         };
     }
     Ok(tokens)
 }
 
-fn parse_num<T: Debug + FromStr>(first: char, iter: &mut Peekable<Enumerate<Chars>>) -> Result<Token<T>, ParseError> {
-    let mut num_str = String::from(first);
+/// The start of the last token if it is a `-` negating whatever follows, rather than subtracting it.
+fn prefix_minus<T>(tokens: &[Token<T>]) -> Option<usize> {
+    let operand = |token: &Token<T>| matches!(token.kind, TokenKind::Value(_) | TokenKind::Variable(_) | TokenKind::ClosingBrackets | TokenKind::ClosingSquareBrackets);
+    match tokens {
+        [.., minus] if !matches!(minus.kind, TokenKind::SubSymbol) => None,
+        [.., before, _] if operand(before) => None,
+        [.., minus] => Some(minus.span.start),
+        [] => None,
+    }
+}
+
+/// Reads a number literal, `minus` is the start of a prefix `-` the literal is negated with if only
+/// the negated value fits into `T`, like `-128` for `i8`.
+fn parse_num<T: Number>(first: char, start: usize, iter: &mut Chars, minus: Option<usize>) -> Result<Token<T>, ParseError> {
+    let (literal, text) = read_literal(first, start, iter)?;
+    let span = Span::new(start, start + text.len());
+    if let Some(v) = T::from_literal(&literal) {
+        return Ok(Token::new(TokenKind::Value(v), span));
+    }
+    match minus.zip(T::from_negated_literal(&literal)) {
+        Some((minus, v)) => Ok(Token::new(TokenKind::Value(v), Span::new(minus, span.end))),
+        None => Err(ParseError::ValueError(text, type_name::<T>().into(), span)),
+    }
+}
+
+/// Reads the literal starting with the digit or `.` `first`, together with the text it was read from.
+fn read_literal(first: char, start: usize, iter: &mut Chars) -> Result<(Literal, String), ParseError> {
+    let mut text = String::from(first);
+    let mut literal = Literal {
+        radix: 10,
+        integer: String::new(),
+        fraction: String::new(),
+        exponent: 0,
+    };
+    let radix = match iter.peek() {
+        Some((_, 'x' | 'X')) if first == '0' => 16,
+        Some((_, 'o' | 'O')) if first == '0' => 8,
+        Some((_, 'b' | 'B')) if first == '0' => 2,
+        _ => 10,
+    };
+    let mut lookahead = iter.clone();
+    lookahead.next();
+    if radix != 10 && matches!(lookahead.peek(), Some((_, c)) if c.is_digit(radix)) {
+        text.push(iter.next().unwrap().1);
+        literal.radix = radix;
+        read_digits(iter, radix, &mut literal.integer, &mut text);
+    } else {
+        if first == '.' {
+            read_digits(iter, 10, &mut literal.fraction, &mut text);
+        } else {
+            literal.integer.push(first);
+            read_digits(iter, 10, &mut literal.integer, &mut text);
+            if let Some((_, '.')) = iter.peek() {
+                text.push(iter.next().unwrap().1);
+                read_digits(iter, 10, &mut literal.fraction, &mut text);
+            }
+        }
+        let mut lookahead = iter.clone();
+        if let Some((_, e @ ('e' | 'E'))) = lookahead.next() {
+            let mut exponent = String::new();
+            if let Some((_, sign @ ('+' | '-'))) = lookahead.peek() {
+                exponent.push(*sign);
+                lookahead.next();
+            }
+            if matches!(lookahead.peek(), Some((_, '0'..='9'))) {
+                text.push(e);
+                text.push_str(&exponent);
+                *iter = lookahead;
+                read_digits(iter, 10, &mut exponent, &mut text);
+                literal.exponent = exponent.parse().map_err(|_| ParseError::LiteralError(text.clone(), Span::new(start, start + text.len())))?;
+            }
+        }
+    }
+    if literal.integer.is_empty() && literal.fraction.is_empty() || matches!(iter.peek(), Some((_, '.'))) {
+        while let Some((_, c @ ('0'..='9' | '.' | '_'))) = iter.peek() {
+            text.push(*c);
+            iter.next();
+        }
+        return Err(ParseError::LiteralError(text.clone(), Span::new(start, start + text.len())));
+    }
+    Ok((literal, text))
+}
+
+/// Reads digits of the given radix into `digits`, a `_` between two digits is skipped as a separator.
+fn read_digits(iter: &mut Chars, radix: u32, digits: &mut String, text: &mut String) {
     while let Some((_, c)) = iter.peek() {
-        match c {
-            '0'..='9' | '.' => num_str.push(iter.next().unwrap().1),
-            _ => break,
+        let c = *c;
+        if c.is_digit(radix) {
+            digits.push(c);
+        } else if c == '_' && !digits.is_empty() {
+            let mut lookahead = iter.clone();
+            lookahead.next();
+            if !matches!(lookahead.peek(), Some((_, c)) if c.is_digit(radix)) {
+                break;
+            }
+        } else {
+            break;
         }
+        text.push(c);
+        iter.next();
+    }
+}
+
+/// Reads `xor`, which is an operator in both identifier modes and therefore cannot name a variable.
+fn parse_keyword<T>(first: char, start: usize, iter: &mut Chars) -> Option<Token<T>> {
+    let mut lookahead = iter.clone();
+    let mut word = String::from(first);
+    while let Some((_, c @ ('a'..='z' | 'A'..='Z' | '0'..='9' | '_'))) = lookahead.peek() {
+        word.push(*c);
+        lookahead.next();
     }
-    match T::from_str(&num_str) {
-        Ok(v) => Ok(Token::Value(v)),
-        Err(_) => Err(ParseError::ValueError(num_str, type_name::<T>().into())),
+    if word != "xor" {
+        return None;
     }
+    *iter = lookahead;
+    Some(Token::new(TokenKind::XorSymbol, Span::new(start, start + word.len())))
 }
 
-fn parse_variable<T: Debug + FromStr>(first: char, iter: &mut Peekable<Enumerate<Chars>>) -> Result<Token<T>, ParseError> {
+/// A registered function of at least two letters or digits directly followed by `(` is called, other letters
+/// before brackets are still multiplied, so `f(x)` is `f*(x)`, `ab(x)` is `a*b*(x)` and `log2(x)` is a call.
+fn parse_function<T>(first: char, start: usize, iter: &mut Chars, functions: &Functions<T>) -> Option<Token<T>> {
+    let mut lookahead = iter.clone();
+    let mut name = String::from(first);
+    while let Some((_, c @ ('a'..='z' | 'A'..='Z' | '0'..='9'))) = lookahead.peek() {
+        name.push(*c);
+        lookahead.next();
+    }
+    if name.len() < 2 || !matches!(lookahead.peek(), Some((_, '('))) || functions.get(&name).is_none() {
+        return None;
+    }
+    *iter = lookahead;
+    let end = start + name.len();
+    Some(Token::new(TokenKind::Function(name), Span::new(start, end)))
+}
+
+/// Reads a whole identifier, which names a function when directly followed by `(`.
+fn parse_word<T>(first: char, start: usize, iter: &mut Chars) -> Token<T> {
+    let mut name = String::from(first);
+    while let Some((_, c @ ('a'..='z' | 'A'..='Z' | '0'..='9' | '_'))) = iter.peek() {
+        name.push(*c);
+        iter.next();
+    }
+    let span = Span::new(start, start + name.len());
+    match iter.peek() {
+        Some((_, '(')) => Token::new(TokenKind::Function(name), span),
+        _ => Token::new(TokenKind::Variable(name), span),
+    }
+}
+
+fn parse_variable<T>(first: char, start: usize, iter: &mut Chars) -> Result<Token<T>, ParseError> {
     match first {
-        'a'..='z' | 'A'..='Z' => Ok(Token::Variable(first.into())),
+        'a'..='z' | 'A'..='Z' => Ok(Token::new(TokenKind::Variable(first.into()), Span::new(start, start + 1))),
         '"' => {
             let mut var = String::new();
-            while let Some((i, c)) = iter.peek() {
+            let mut end = start + 1;
+            for (i, c) in iter.by_ref() {
+                end = i + c.len_utf8();
                 match c {
-                    'a'..='z' | 'A'..='Z' => var.push(iter.next().unwrap().1),
-                    '"' => {
-                        iter.next();
-                        break;
-                    }
-                    _ => return Err(ParseError::UnexpectedTokenError(*i, first)),
+                    'a'..='z' | 'A'..='Z' => var.push(c),
+                    '"' => break,
+                    _ => return Err(ParseError::UnexpectedTokenError(c.into(), Span::new(i, end))),
                 }
             }
-            Ok(Token::Variable(var))
+            Ok(Token::new(TokenKind::Variable(var), Span::new(start, end)))
         }
-        _ => Err(ParseError::UnexpectedTokenError(iter.peek().unwrap().0 - 1, first)),
+        _ => Err(ParseError::UnexpectedTokenError(first.into(), Span::new(start, start + first.len_utf8()))),
     }
 }
 
-pub fn parse_string<T: Debug + FromStr + PartialEq + Clone>(input: Vec<Token<T>>) -> Result<Expr<T>, ParseError> {
-    trace!("Parsing {input:?}");
-    if input.is_empty() {
-        return Err(ParseError::EmptyError);
-    } else if input.len() == 1 {
-        if let Some(Token::Value(v)) = input.get(0) {
-            return Ok(Expr::Value(v.clone()));
-        } else if let Some(Token::Variable(v)) = input.get(0) {
-            return Ok(Expr::Variable(v.clone()));
-        }
-    } else {
-        let operand = split_at_major_operand(input);
-        let recurse = |d: Vec<Token<T>>| -> Result<Box<Expr<T>>, ParseError> { Ok(Box::new(parse_string::<T>(d)?)) };
-        return match operand {
-            (Token::AddSymbol, left, right) => Ok(Add(recurse(left)?, recurse(right)?)),
-            (Token::SubSymbol, left, right) => Ok(Sub(recurse(left)?, recurse(right)?)),
-            (Token::MulSymbol, left, right) => Ok(Mul(recurse(left)?, recurse(right)?)),
-            (Token::DivSymbol, left, right) => Ok(Div(recurse(left)?, recurse(right)?)),
-            (Token::PowSymbol, left, right) => Ok(Pow(recurse(left)?, recurse(right)?)),
-            (Token::RemSymbol, left, right) => Ok(Rem(recurse(left)?, recurse(right)?)),
-            _ => unreachable!(),
-        };
+/// Binding power of the binary operators, from loosest to tightest:
+///
+/// | level | operators                       | associativity |
+/// |-------|---------------------------------|---------------|
+/// | 1     | `? :`                           | right         |
+/// | 2     | `\|\|`                           | left          |
+/// | 3     | `&&`                            | left          |
+/// | 4     | `==` `!=` `<` `<=` `>` `>=`     | left          |
+/// | 5     | `\|`                            | left          |
+/// | 6     | `xor`                           | left          |
+/// | 7     | `&`                             | left          |
+/// | 8     | `<<` `>>`                       | left          |
+/// | 9     | `+` `-`                         | left          |
+/// | 10    | `*` `/` `%`                     | left          |
+/// | 11    | `±`                             | left          |
+/// | 11    | prefix `-` `+` `!` `~`          | right         |
+/// | 12    | `^`                             | right         |
+///
+/// Prefix operators bind looser than `^`, so `-2^2` is `-(2^2)`, while `2^-1` still parses.
+/// Their operand takes a `±` along, so `-a±b` is `-(a±b)`, and `2*a±b` is `2*(a±b)` while `a^2±1` is `(a^2)±1`.
+/// The bitwise operators follow Rust, so `a & b == c` compares the masked value.
+///
+/// Returns the left and right binding power, a left associative operator binds tighter to its right
+/// and a right associative operator binds tighter to its left.
+fn binding_power<T>(token: &TokenKind<T>) -> Option<(u8, u8)> {
+    match token {
+        TokenKind::QuestionSymbol => Some((2, 1)),
+        TokenKind::OrSymbol => Some((3, 4)),
+        TokenKind::AndSymbol => Some((5, 6)),
+        TokenKind::EqSymbol | TokenKind::NeSymbol | TokenKind::LtSymbol | TokenKind::LeSymbol | TokenKind::GtSymbol | TokenKind::GeSymbol => Some((7, 8)),
+        TokenKind::BitOrSymbol => Some((9, 10)),
+        TokenKind::XorSymbol => Some((11, 12)),
+        TokenKind::BitAndSymbol => Some((13, 14)),
+        TokenKind::ShlSymbol | TokenKind::ShrSymbol => Some((15, 16)),
+        TokenKind::AddSymbol | TokenKind::SubSymbol => Some((17, 18)),
+        TokenKind::MulSymbol | TokenKind::DivSymbol | TokenKind::RemSymbol => Some((19, 20)),
+        TokenKind::PlusMinusSymbol => Some((21, 22)),
+        TokenKind::PowSymbol => Some((23, 22)),
+        _ => None,
     }
-    unreachable!();
 }
 
-type OperandRest<T> = (Token<T>, Vec<Token<T>>, Vec<Token<T>>);
+/// Binding power of the operand of a prefix operator, see [`binding_power`].
+const PREFIX_POWER: u8 = 21;
 
-#[derive(Debug)]
-struct Operand<T> {
-    token: Token<T>,
-    index: usize,
-    value: usize,
-    level: usize,
+pub fn parse_string<T: Debug + Clone>(input: Vec<Token<T>>) -> Result<Expr<T>, ParseError> {
+    trace!("Parsing {input:?}");
+    let end = input.last().map(|t| t.span.end).unwrap_or_default();
+    let mut parser = Parser {
+        tokens: input.into_iter().peekable(),
+        end,
+    };
+    if parser.tokens.peek().is_none() {
+        return Err(ParseError::EmptyError(Span::new(0, 0)));
+    }
+    let expr = parser.parse_expr(0)?;
+    match parser.tokens.next() {
+        None => Ok(expr),
+        Some(token) => Err(unexpected(&token)),
+    }
 }
 
-fn split_at_major_operand<T: Debug + PartialEq + Clone>(input: Vec<Token<T>>) -> OperandRest<T> {
-    let mut level = 0;
-    let mut operand = Operand {
-        token: Token::Variable("no operands".into()),
-        index: 0,
-        value: 0,
-        level: 1000,
-    };
-    for (index, token) in input.iter().enumerate() {
-        match token {
-            Token::OpeningBrackets => {
-                level += 1;
+struct Parser<T> {
+    tokens: Peekable<std::vec::IntoIter<Token<T>>>,
+    end: usize,
+}
+
+impl<T: Debug + Clone> Parser<T> {
+    fn parse_expr(&mut self, min_power: u8) -> Result<Expr<T>, ParseError> {
+        let mut left = self.parse_primary()?;
+        while let Some(token) = self.tokens.peek() {
+            let Some((left_power, right_power)) = binding_power(&token.kind) else {
+                break;
+            };
+            if left_power < min_power {
+                break;
             }
-            Token::ClosingBrackets => {
-                level -= 1;
+            let operator = self.tokens.next().unwrap();
+            if matches!(operator.kind, TokenKind::QuestionSymbol) {
+                left = self.parse_conditional(left, right_power)?;
+                continue;
             }
-            _ => {
-                let value = operand_value(token);
-                if level < operand.level || (level == operand.level && value >= operand.value) {
-                    operand = Operand {
-                        token: token.clone(),
-                        index,
-                        value,
-                        level,
-                    };
+            let right = self.parse_expr(right_power)?;
+            let span = left.span.to(right.span);
+            let (left_box, right_box) = (Box::new(left), Box::new(right));
+            let kind = match operator.kind {
+                TokenKind::AddSymbol => ExprKind::Add(left_box, right_box),
+                TokenKind::SubSymbol => ExprKind::Sub(left_box, right_box),
+                TokenKind::MulSymbol => ExprKind::Mul(left_box, right_box),
+                TokenKind::DivSymbol => ExprKind::Div(left_box, right_box),
+                TokenKind::PowSymbol => ExprKind::Pow(left_box, right_box),
+                TokenKind::PlusMinusSymbol => ExprKind::Uncertain(left_box, right_box),
+                TokenKind::RemSymbol => ExprKind::Rem(left_box, right_box),
+                TokenKind::EqSymbol => ExprKind::Eq(left_box, right_box),
+                TokenKind::NeSymbol => ExprKind::Ne(left_box, right_box),
+                TokenKind::LtSymbol => ExprKind::Lt(left_box, right_box),
+                TokenKind::LeSymbol => ExprKind::Le(left_box, right_box),
+                TokenKind::GtSymbol => ExprKind::Gt(left_box, right_box),
+                TokenKind::GeSymbol => ExprKind::Ge(left_box, right_box),
+                TokenKind::AndSymbol => ExprKind::And(left_box, right_box),
+                TokenKind::OrSymbol => ExprKind::Or(left_box, right_box),
+                TokenKind::BitAndSymbol => ExprKind::BitAnd(left_box, right_box),
+                TokenKind::BitOrSymbol => ExprKind::BitOr(left_box, right_box),
+                TokenKind::XorSymbol => ExprKind::BitXor(left_box, right_box),
+                TokenKind::ShlSymbol => ExprKind::Shl(left_box, right_box),
+                TokenKind::ShrSymbol => ExprKind::Shr(left_box, right_box),
+                _ => unreachable!(),
+            };
+            left = Expr::new(kind, span);
+            trace!("Reduced to {left:?}");
+        }
+        Ok(left)
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr<T>, ParseError> {
+        let Some(token) = self.tokens.next() else {
+            return Err(ParseError::UnexpectedEndError(Span::new(self.end, self.end)));
+        };
+        match token.kind {
+            TokenKind::Value(v) => Ok(Expr::new(ExprKind::Value(v), token.span)),
+            TokenKind::Variable(v) => Ok(Expr::new(ExprKind::Variable(v), token.span)),
+            TokenKind::Function(name) => self.parse_call(name, token.span),
+            TokenKind::SubSymbol => {
+                let operand = self.parse_expr(PREFIX_POWER)?;
+                let span = token.span.to(operand.span);
+                Ok(Expr::new(ExprKind::Neg(Box::new(operand)), span))
+            }
+            TokenKind::NotSymbol => {
+                let operand = self.parse_expr(PREFIX_POWER)?;
+                let span = token.span.to(operand.span);
+                Ok(Expr::new(ExprKind::Not(Box::new(operand)), span))
+            }
+            TokenKind::BitNotSymbol => {
+                let operand = self.parse_expr(PREFIX_POWER)?;
+                let span = token.span.to(operand.span);
+                Ok(Expr::new(ExprKind::BitNot(Box::new(operand)), span))
+            }
+            TokenKind::AddSymbol => {
+                let mut operand = self.parse_expr(PREFIX_POWER)?;
+                operand.span = token.span.to(operand.span);
+                Ok(operand)
+            }
+            TokenKind::OpeningBrackets => {
+                if matches!(self.tokens.peek(), Some(Token { kind: TokenKind::ClosingBrackets, .. })) {
+                    let closing = self.tokens.next().unwrap();
+                    return Err(ParseError::EmptyError(token.span.to(closing.span)));
+                }
+                let mut expr = self.parse_expr(0)?;
+                match self.tokens.next() {
+                    Some(Token { kind: TokenKind::ClosingBrackets, span }) => {
+                        expr.span = token.span.to(span);
+                        Ok(expr)
+                    }
+                    Some(token) => Err(unexpected(&token)),
+                    None => Err(ParseError::UnexpectedEndError(Span::new(self.end, self.end))),
                 }
             }
+            TokenKind::OpeningSquareBrackets => self.parse_interval(token.span),
+            _ => Err(unexpected(&token)),
+        }
+    }
+
+    /// Parses the bounds of `[lo, hi]` after the `[`.
+    fn parse_interval(&mut self, opening: Span) -> Result<Expr<T>, ParseError> {
+        let lo = self.parse_expr(0)?;
+        match self.tokens.next() {
+            Some(Token { kind: TokenKind::Comma, .. }) => {}
+            Some(token) => return Err(unexpected(&token)),
+            None => return Err(ParseError::UnexpectedEndError(Span::new(self.end, self.end))),
+        }
+        let hi = self.parse_expr(0)?;
+        match self.tokens.next() {
+            Some(Token { kind: TokenKind::ClosingSquareBrackets, span }) => Ok(Expr::new(ExprKind::Interval(Box::new(lo), Box::new(hi)), opening.to(span))),
+            Some(token) => Err(unexpected(&token)),
+            None => Err(ParseError::UnexpectedEndError(Span::new(self.end, self.end))),
         }
     }
-    let (left, right) = input.split_at(operand.index);
-    let left = left[operand.level..].to_vec();
-    let right = right[1..right.len() - operand.level].to_vec();
-    trace!("Split at {operand:?} with left {left:?} and right {right:?}");
-    (operand.token, left, right)
-}
 
-fn operand_value<T: Debug>(o: &Token<T>) -> usize {
-    match o {
-        Token::AddSymbol => 10,
-        Token::SubSymbol => 9,
-        Token::MulSymbol => 8,
-        Token::DivSymbol => 7,
-        Token::RemSymbol => 6,
-        Token::PowSymbol => 5,
-        _ => 0,
+    /// Parses the branches of `condition ? then : otherwise` after the `?`.
+    fn parse_conditional(&mut self, condition: Expr<T>, right_power: u8) -> Result<Expr<T>, ParseError> {
+        let then = self.parse_expr(0)?;
+        match self.tokens.next() {
+            Some(Token { kind: TokenKind::ColonSymbol, .. }) => {}
+            Some(token) => return Err(unexpected(&token)),
+            None => return Err(ParseError::UnexpectedEndError(Span::new(self.end, self.end))),
+        }
+        let otherwise = self.parse_expr(right_power)?;
+        let span = condition.span.to(otherwise.span);
+        Ok(Expr::new(ExprKind::Conditional(Box::new(condition), Box::new(then), Box::new(otherwise)), span))
     }
+
+    /// Parses the bracketed, comma separated arguments following a function name.
+    fn parse_call(&mut self, name: String, name_span: Span) -> Result<Expr<T>, ParseError> {
+        match self.tokens.next() {
+            Some(Token { kind: TokenKind::OpeningBrackets, .. }) => {}
+            Some(token) => return Err(unexpected(&token)),
+            None => return Err(ParseError::UnexpectedEndError(Span::new(self.end, self.end))),
+        }
+        let mut args = Vec::new();
+        if let Some(Token { kind: TokenKind::ClosingBrackets, .. }) = self.tokens.peek() {
+            let closing = self.tokens.next().unwrap();
+            return Ok(Expr::new(ExprKind::Call(name, args), name_span.to(closing.span)));
+        }
+        loop {
+            args.push(self.parse_expr(0)?);
+            match self.tokens.next() {
+                Some(Token { kind: TokenKind::Comma, .. }) => continue,
+                Some(Token { kind: TokenKind::ClosingBrackets, span }) => return Ok(Expr::new(ExprKind::Call(name, args), name_span.to(span))),
+                Some(token) => return Err(unexpected(&token)),
+                None => return Err(ParseError::UnexpectedEndError(Span::new(self.end, self.end))),
+            }
+        }
+    }
+}
+
+fn unexpected<T: Debug>(token: &Token<T>) -> ParseError {
+    ParseError::UnexpectedTokenError(token.kind.symbol(), token.span)
 }
@@ -0,0 +1,146 @@
+use crate::builtins::Elementary;
+use crate::decimal::context;
+use crate::number::{Number, NumberError};
+use crate::parse::Literal;
+use num::{BigInt, BigRational, Integer, One, Signed, ToPrimitive, Zero};
+use std::cmp::Ordering;
+
+/// Raises to an integer power, [`NumberError::Overflow`] if the result would have more digits than the
+/// [`Context`](crate::decimal::Context) allows.
+fn powi(base: BigRational, exponent: &BigInt) -> Result<BigRational, NumberError> {
+    if base.is_zero() {
+        return match exponent.sign() {
+            num::bigint::Sign::Minus => Err(NumberError::DivisionByZero),
+            num::bigint::Sign::NoSign => Ok(BigRational::one()),
+            num::bigint::Sign::Plus => Ok(base),
+        };
+    }
+    if Signed::abs(&base).is_one() {
+        return Ok(if exponent.is_even() { Signed::abs(&base) } else { base });
+    }
+    let width = base.numer().bits().max(base.denom().bits()) as f64 * std::f64::consts::LOG10_2;
+    let power = exponent
+        .abs()
+        .to_u32()
+        .filter(|&power| power as f64 * width <= context().max_digits as f64)
+        .ok_or(NumberError::Overflow)?;
+    let result = BigRational::new(base.numer().pow(power), base.denom().pow(power));
+    Ok(if exponent.is_negative() { result.recip() } else { result })
+}
+
+fn exact_sqrt(value: &BigInt) -> Option<BigInt> {
+    let root = value.sqrt();
+    (&root * &root == *value).then_some(root)
+}
+
+/// Exact fractions, every operation except `^` with a non-integer exponent stays exact.
+impl Number for BigRational {
+    fn from_literal(literal: &Literal) -> Option<Self> {
+        if literal.radix != 10 {
+            return BigInt::parse_bytes(literal.integer.as_bytes(), literal.radix).map(BigRational::from_integer);
+        }
+        let (digits, exponent) = literal.to_digits();
+        let scale = powi(BigRational::from_integer(BigInt::from(10)), &BigInt::from(exponent)).ok()?;
+        Some(BigRational::from_integer(digits.parse().ok()?) * scale)
+    }
+
+    fn try_add(self, rhs: Self) -> Result<Self, NumberError> {
+        Ok(self + rhs)
+    }
+
+    fn try_sub(self, rhs: Self) -> Result<Self, NumberError> {
+        Ok(self - rhs)
+    }
+
+    fn try_mul(self, rhs: Self) -> Result<Self, NumberError> {
+        Ok(self * rhs)
+    }
+
+    fn try_div(self, rhs: Self) -> Result<Self, NumberError> {
+        if rhs.is_zero() {
+            return Err(NumberError::DivisionByZero);
+        }
+        Ok(self / rhs)
+    }
+
+    /// The remainder of the truncated quotient, it has the sign of `self` like `%` of the other types.
+    fn try_rem(self, rhs: Self) -> Result<Self, NumberError> {
+        if rhs.is_zero() {
+            return Err(NumberError::DivisionByZero);
+        }
+        Ok(self % rhs)
+    }
+
+    fn try_pow(self, rhs: Self) -> Result<Self, NumberError> {
+        if !rhs.is_integer() {
+            return Err(NumberError::Domain(format!("{self} cannot be raised to the non-integer power {rhs}, the result would not be exact")));
+        }
+        powi(self, rhs.numer())
+    }
+
+    fn try_neg(self) -> Result<Self, NumberError> {
+        Ok(-self)
+    }
+
+    fn from_bool(value: bool) -> Self {
+        BigRational::from_integer(u8::from(value).into())
+    }
+
+    fn try_cmp(&self, other: &Self) -> Result<Option<Ordering>, NumberError> {
+        Ok(Some(self.cmp(other)))
+    }
+
+    fn to_ratio(&self) -> Option<BigRational> {
+        Some(self.clone())
+    }
+
+    fn to_float(&self) -> Option<f64> {
+        ToPrimitive::to_f64(self)
+    }
+}
+
+/// Only the functions with exact results are supported, square roots only of perfect squares.
+impl Elementary for BigRational {
+    fn sqrt(self) -> Result<Self, NumberError> {
+        if self.is_negative() {
+            return Err(NumberError::Domain(format!("square root of negative number {self}")));
+        }
+        match (exact_sqrt(self.numer()), exact_sqrt(self.denom())) {
+            (Some(numerator), Some(denominator)) => Ok(BigRational::new(numerator, denominator)),
+            _ => Err(NumberError::Domain(format!("square root of {self} is not rational"))),
+        }
+    }
+
+    fn abs(self) -> Result<Self, NumberError> {
+        Ok(Signed::abs(&self))
+    }
+
+    fn sign(self) -> Result<Self, NumberError> {
+        Ok(self.signum())
+    }
+
+    fn floor(self) -> Result<Self, NumberError> {
+        Ok(BigRational::floor(&self))
+    }
+
+    fn ceil(self) -> Result<Self, NumberError> {
+        Ok(BigRational::ceil(&self))
+    }
+
+    /// Rounds half away from zero.
+    fn round(self) -> Result<Self, NumberError> {
+        Ok(BigRational::round(&self))
+    }
+
+    fn trunc(self) -> Result<Self, NumberError> {
+        Ok(BigRational::trunc(&self))
+    }
+
+    fn minimum(self, other: Self) -> Result<Self, NumberError> {
+        Ok(Ord::min(self, other))
+    }
+
+    fn maximum(self, other: Self) -> Result<Self, NumberError> {
+        Ok(Ord::max(self, other))
+    }
+}
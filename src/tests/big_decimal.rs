@@ -0,0 +1,104 @@
+use crate::big_decimal::BigDecimal;
+use crate::decimal::{set_context, Context};
+use crate::eval::EvalError;
+use crate::parse::Span;
+use crate::{solve_with, Error};
+use num::BigInt;
+
+fn big(value: &str, scale: i64) -> BigDecimal {
+    BigDecimal::new(value.parse().unwrap(), scale)
+}
+
+fn solve(input: &str) -> Result<BigDecimal, Error> {
+    solve_with(input.into())
+}
+
+#[test]
+fn display() {
+    assert_eq!(big("105", 2).to_string(), "1.05");
+    assert_eq!(big("-5", 1).to_string(), "-0.5");
+    assert_eq!(big("-5", 3).to_string(), "-0.005");
+    assert_eq!(big("12", -3).to_string(), "12000");
+    assert_eq!(big("1200", 2).to_string(), "12");
+    assert_eq!(big("0", 7).to_string(), "0");
+}
+
+#[test]
+fn arithmetic() {
+    assert_eq!(solve("0.1+0.2"), Ok(big("3", 1)));
+    assert_eq!(solve("0.5*0.5"), Ok(big("25", 2)));
+    assert_eq!(solve("7.5%2"), Ok(big("15", 1)));
+    assert_eq!(solve("1e40*1e40"), Ok(big("1", -80)));
+    assert_eq!(solve("123456789012345678901234567890123456789 + 1"), Ok(big("123456789012345678901234567890123456790", 0)));
+    assert_eq!(solve("1.10 == 1.1"), Ok(big("1", 0)));
+}
+
+#[test]
+fn large_scales() {
+    assert_eq!(solve("1e-300 * 1e-300"), Ok(big("1", 600)));
+    assert_eq!(solve("1e-300 < 1e-299"), Ok(big("1", 0)));
+    assert_eq!(solve("2^-10"), Ok(big("9765625", 10)));
+}
+
+#[test]
+fn precision() {
+    assert_eq!(solve("1/3"), Ok(big(&"3".repeat(38), 38)));
+    set_context(Context { precision: 60, ..Context::default() });
+    assert_eq!(solve("1/7"), Ok(big(&"142857".repeat(10), 60)));
+    assert_eq!(solve("2^200"), Ok(big("160693804425899027554196209234116260252220299378279283530138", -1)));
+    set_context(Context { precision: 100, ..Context::default() });
+    assert_eq!(solve("2^200"), Ok(BigDecimal::new(BigInt::from(2).pow(200), 0)));
+    set_context(Context::default());
+}
+
+#[test]
+fn limits() {
+    let overflow = |end| Err(Error::EvalError(EvalError::Overflow(Span::new(0, end))));
+    assert_eq!(solve("9^9^9"), overflow(5));
+    assert_eq!(solve("0.5^100000"), overflow(10));
+    set_context(Context { max_digits: 50, ..Context::default() });
+    assert_eq!(solve("1e48 * 10"), Ok(big("1", -49)));
+    assert_eq!(solve("1e49 * 10"), overflow(9));
+    assert_eq!(solve("1e49 + 1e-1"), overflow(11));
+    assert!(matches!(solve("1e50"), Err(Error::ParseError(_))));
+    set_context(Context::default());
+}
+
+#[test]
+fn functions() {
+    assert_eq!(solve("floor(0-2.5)"), Ok(big("-3", 0)));
+    assert_eq!(solve("ceil(2.1)"), Ok(big("3", 0)));
+    assert_eq!(solve("round(2.5)"), Ok(big("3", 0)));
+    assert_eq!(solve("trunc(0-2.7)"), Ok(big("-2", 0)));
+    assert_eq!(solve("max(1e-100, 0.5, 1e100)"), Ok(big("1", -100)));
+    assert_eq!(solve("sqrt(2)"), Ok(big("14142135623730950488016887242096980786", 37)));
+}
+
+#[test]
+fn transcendental() {
+    set_context(Context { precision: 50, ..Context::default() });
+    assert_eq!(solve("sqrt(2)"), Ok(big("14142135623730950488016887242096980785696718753769", 49)));
+    assert_eq!(solve("sqrt(1e400)"), Ok(big("1", -200)));
+    assert_eq!(solve("cbrt(0-27)"), Ok(big("-3", 0)));
+    assert_eq!(solve("exp(1)"), Ok(big("27182818284590452353602874713526624977572470937000", 49)));
+    assert_eq!(solve("exp(0-100)"), Ok(big("37200759760208359629596958038631183373588922923768", 93)));
+    assert_eq!(solve("ln(1e500)"), Ok(big("11512925464970228420089957273421821038005507443144", 46)));
+    assert_eq!(solve("ln(1.0000000001)"), Ok(big("99999999995000000000333333333308333333335333333333", 60)));
+    assert_eq!(solve("sin(1e30)"), Ok(big("-90116901912138058030386428952987330274396332993043", 51)));
+    assert_eq!(solve("atan(1) * 4"), solve("pi()"));
+    assert_eq!(solve("acos(0.9999999999)"), Ok(big("14142135623848801618217296813259983317431575920216", 54)));
+    assert_eq!(solve("log10(1000)"), Ok(big("3", 0)));
+    assert_eq!(solve("log2(0.125)"), Ok(big("-3", 0)));
+    set_context(Context::default());
+}
+
+#[test]
+fn fractional_powers() {
+    assert_eq!(solve("2^0.5"), solve("sqrt(2)"));
+    assert_eq!(solve("4^1.5"), Ok(big("8", 0)));
+    assert_eq!(solve("1e-300^0.5"), Ok(big("1", 150)));
+    set_context(Context { precision: 50, ..Context::default() });
+    assert_eq!(solve("3^0.3333"), Ok(big("14421967555044219437000276149948307531576630122173", 49)));
+    set_context(Context::default());
+    assert!(matches!(solve("10^20000.5"), Err(Error::EvalError(EvalError::Overflow(_)))));
+}
@@ -0,0 +1,71 @@
+use crate::decimal::{set_context, Context};
+use crate::eval::EvalError;
+use crate::parse::{ParseError, Span};
+use crate::{solve_with, Error};
+use num::BigInt;
+
+fn big(value: &str) -> BigInt {
+    value.parse().unwrap()
+}
+
+fn solve(input: &str) -> Result<BigInt, Error> {
+    solve_with(input.into())
+}
+
+#[test]
+fn arithmetic() {
+    assert_eq!(solve("2^128 + 1"), Ok(big("340282366920938463463374607431768211457")));
+    assert_eq!(solve("3 - 5"), Ok(big("-2")));
+    assert_eq!(solve("-7 / 2"), Ok(big("-3")));
+    assert_eq!(solve("-7 % 3"), Ok(big("-1")));
+    assert_eq!(solve("1e30 / 7"), Ok(big("142857142857142857142857142857")));
+    assert_eq!(solve("0xff + 0b1"), Ok(big("256")));
+    assert_eq!(solve("1/0"), Err(Error::EvalError(EvalError::DivisionByZero(Span::new(0, 3)))));
+}
+
+#[test]
+fn literals() {
+    assert_eq!(
+        solve("12e-1"),
+        Err(Error::ParseError(ParseError::ValueError("12e-1".into(), "num_bigint::bigint::BigInt".into(), Span::new(0, 5))))
+    );
+    assert_eq!(solve("120e-1"), Ok(big("12")));
+    assert_eq!(solve("0.0"), Ok(big("0")));
+    assert!(matches!(solve("1.5"), Err(Error::ParseError(ParseError::ValueError(..)))));
+    assert!(matches!(solve("1e99999999"), Err(Error::ParseError(ParseError::ValueError(..)))));
+}
+
+#[test]
+fn pow() {
+    assert_eq!(solve("2^100"), Ok(big("1267650600228229401496703205376")));
+    assert_eq!(solve("(-2)^3"), Ok(big("-8")));
+    assert_eq!(solve("(-1)^100000000000000000001"), Ok(big("-1")));
+    assert_eq!(solve("0^0"), Ok(big("1")));
+    assert_eq!(solve("9^9^9"), Err(Error::EvalError(EvalError::Overflow(Span::new(0, 5)))));
+    assert_eq!(
+        solve("2^-1"),
+        Err(Error::EvalError(EvalError::Domain(
+            "2 cannot be raised to the negative power -1, the result would not be an integer".into(),
+            Span::new(0, 4)
+        )))
+    );
+}
+
+#[test]
+fn limits() {
+    assert_eq!(solve("10^9999").map(|v| v.to_string().len()), Ok(10000));
+    assert_eq!(solve("10^10000"), Err(Error::EvalError(EvalError::Overflow(Span::new(0, 8)))));
+    assert_eq!(solve("10^9999*10"), Err(Error::EvalError(EvalError::Overflow(Span::new(0, 10)))));
+    set_context(Context { max_digits: 20, ..Context::default() });
+    assert_eq!(solve("2^64"), Ok(big("18446744073709551616")));
+    assert_eq!(solve("2^64 * 10"), Err(Error::EvalError(EvalError::Overflow(Span::new(0, 9)))));
+    set_context(Context::default());
+}
+
+#[test]
+fn functions() {
+    assert_eq!(solve("sqrt(99)"), Ok(big("9")));
+    assert_eq!(solve("cbrt(-28)"), Ok(big("-3")));
+    assert_eq!(solve("abs(-5) + sign(-5)"), Ok(big("4")));
+    assert_eq!(solve("max(2^70, 3^45)"), Ok(big("2954312706550833698643")));
+}
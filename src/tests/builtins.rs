@@ -0,0 +1,93 @@
+use crate::decimal::Decimal;
+use crate::eval::EvalError;
+use crate::parse::Span;
+use crate::{solve, solve_vars, solve_with, Error};
+use num::complex::Complex64;
+use std::collections::HashMap;
+use std::f64::consts::{E, PI};
+use std::str::FromStr;
+
+fn assert_nearly_eq(input: &str, expected: f64) {
+    let result = solve(input.into()).unwrap();
+    assert!((result - expected).abs() < 1e-12, "{input} = {result}, expected {expected}");
+}
+
+fn decimal(input: &str) -> Decimal {
+    Decimal::from_str(input).unwrap()
+}
+
+#[test]
+fn float_functions() {
+    assert_eq!(solve("sqrt(16)".into()), Ok(4.0));
+    assert_eq!(solve("cbrt(27)".into()), Ok(3.0));
+    assert_nearly_eq("exp(1)", E);
+    assert_nearly_eq("ln(exp(2))", 2.0);
+    assert_eq!(solve("log10(1000)".into()), Ok(3.0));
+    assert_eq!(solve("log2(8)".into()), Ok(3.0));
+    assert_nearly_eq("log(3, 81)", 4.0);
+    assert_nearly_eq("sin(0)+cos(0)", 1.0);
+    assert_nearly_eq("tan(atan(0.5))", 0.5);
+    assert_nearly_eq("asin(1)*2", PI);
+    assert_nearly_eq("acos(-1)", PI);
+    assert_nearly_eq("cosh(0)+sinh(0)+tanh(0)", 1.0);
+    assert_nearly_eq("asinh(sinh(1))+acosh(cosh(1))+atanh(tanh(0.5))", 2.5);
+    assert_eq!(solve("abs(-3)*sign(-2)".into()), Ok(-3.0));
+    assert_eq!(solve("sign(0)".into()), Ok(0.0));
+    assert_eq!(solve("min(3, 1, 2)+max(3, 1, 2)".into()), Ok(4.0));
+    assert_eq!(solve("floor(-1.5)+ceil(-1.5)+round(2.5)+trunc(-2.7)".into()), Ok(-2.0));
+}
+
+#[test]
+fn integer_functions() {
+    assert_eq!(solve_with::<u32>("sqrt(17)".into()), Ok(4));
+    assert_eq!(solve_with::<u32>("cbrt(64)+cbrt(63)".into()), Ok(7));
+    assert_eq!(solve_with::<u8>("cbrt(255)".into()), Ok(6));
+    assert_eq!(solve_with::<u32>("log2(1024)+log10(999)+log(3, 28)".into()), Ok(15));
+    assert_eq!(solve_with::<u32>("min(4, 2, 8)*max(4, 2, 8)".into()), Ok(16));
+    assert_eq!(solve_with::<u32>("abs(5)+sign(5)+sign(0)+floor(3)".into()), Ok(9));
+    assert_eq!(solve_with::<usize>("log2(0)".into()), Err(Error::EvalError(EvalError::Domain("logarithm of zero".into(), Span::new(0, 7)))));
+}
+
+#[test]
+fn unsupported_functions() {
+    assert_eq!(
+        solve_with::<u32>("1 + sin(1)".into()),
+        Err(Error::EvalError(EvalError::Unsupported("sin", "u32".into(), Span::new(4, 10))))
+    );
+    assert_eq!(
+        solve_with::<u8>("exp(2)".into()),
+        Err(Error::EvalError(EvalError::Unsupported("exp", "u8".into(), Span::new(0, 6))))
+    );
+}
+
+#[test]
+fn decimal_functions() {
+    assert_eq!(solve_with::<Decimal>("sqrt(2)".into()), Ok(decimal("1.4142135623730950488016887242096980786")));
+    assert_eq!(solve_with::<Decimal>("sqrt(6.25)".into()), Ok(decimal("2.5")));
+    assert_eq!(solve_with::<Decimal>("ln(1)".into()), Ok(decimal("0")));
+    assert_eq!(solve_with::<Decimal>("log10(1000)".into()), Ok(decimal("3")));
+    assert_eq!(solve_with::<Decimal>("abs(0-2.5)".into()), Ok(decimal("2.5")));
+    assert_eq!(solve_with::<Decimal>("min(2.5, 2.25)+max(1, 0.5)".into()), Ok(decimal("3.25")));
+    assert_eq!(solve_with::<Decimal>("floor(-1.5)".into()), Ok(decimal("-2")));
+    assert_eq!(solve_with::<Decimal>("ceil(-1.5)".into()), Ok(decimal("-1")));
+    assert_eq!(solve_with::<Decimal>("ceil(-170141183460469231731687303715884105727 - 1)".into()), Ok(Decimal::new(i128::MIN, 0)));
+    assert_eq!(solve_with::<Decimal>("round(2.5)+round(-2.5)".into()), Ok(decimal("0")));
+    assert_eq!(solve_with::<Decimal>("round(2.45)".into()), Ok(decimal("2")));
+    assert_eq!(solve_with::<Decimal>("trunc(-2.7)".into()), Ok(decimal("-2")));
+    assert!(matches!(solve_with::<Decimal>("sqrt(-4)".into()), Err(Error::EvalError(EvalError::Domain(_, _)))));
+    assert!(matches!(solve_with::<Decimal>("ln(0)".into()), Err(Error::EvalError(EvalError::Domain(_, _)))));
+}
+
+#[test]
+fn constants() {
+    assert_eq!(solve("pi()".into()), Ok(PI));
+    assert_eq!(solve("2e".into()), Ok(2.0 * E));
+    assert_eq!(solve("\"pi\"/2".into()), Ok(PI / 2.0));
+    let variables = HashMap::from([("e".to_string(), 1.0)]);
+    assert_eq!(solve_vars("e+1".into(), &variables), Ok(2.0));
+    assert_eq!(solve_with::<Complex64>("e^(i*pi())".into()).map(|z| z.re), Ok(-1.0));
+    assert_eq!(
+        solve_with::<u32>("pi()".into()),
+        Err(Error::EvalError(EvalError::Unsupported("pi", "u32".into(), Span::new(0, 4))))
+    );
+}
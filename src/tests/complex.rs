@@ -0,0 +1,53 @@
+use crate::eval::EvalError;
+use crate::parse::Span;
+use crate::{solve_with, Error};
+use num::complex::Complex64;
+
+fn solve(input: &str) -> Result<Complex64, Error> {
+    solve_with(input.into())
+}
+
+fn close(input: &str, re: f64, im: f64) {
+    let value = solve(input).unwrap();
+    assert!((value - Complex64::new(re, im)).norm() < 1e-12, "{input} = {value}, expected {re}+{im}i");
+}
+
+#[test]
+fn arithmetic() {
+    assert_eq!(solve("(1+2i)*(3-i)"), Ok(Complex64::new(5.0, 5.0)));
+    assert_eq!(solve("1/i"), Ok(Complex64::new(0.0, -1.0)));
+    assert_eq!(solve("-i"), Ok(Complex64::new(0.0, -1.0)));
+    assert_eq!(solve("2j + 1"), Ok(Complex64::new(1.0, 2.0)));
+    assert_eq!(solve("i == j"), Ok(Complex64::new(1.0, 0.0)));
+    assert!(solve("1/0").unwrap().is_nan());
+}
+
+#[test]
+fn pow() {
+    assert_eq!(solve("i^2"), Ok(Complex64::new(-1.0, 0.0)));
+    assert_eq!(solve("(1+i)^-2"), Ok(Complex64::new(0.0, -0.5)));
+    close("i^i", (-std::f64::consts::FRAC_PI_2).exp(), 0.0);
+    close("(0-4)^0.5", 0.0, 2.0);
+}
+
+#[test]
+fn functions() {
+    assert_eq!(solve("sqrt(-4)"), Ok(Complex64::new(0.0, 2.0)));
+    assert_eq!(solve("abs(3+4i)"), Ok(Complex64::new(5.0, 0.0)));
+    assert_eq!(solve("conj(1+2i)"), Ok(Complex64::new(1.0, -2.0)));
+    assert_eq!(solve("re(1+2i) + im(1+2i)"), Ok(Complex64::new(3.0, 0.0)));
+    assert_eq!(solve("arg(-1)"), Ok(Complex64::new(std::f64::consts::PI, 0.0)));
+    assert_eq!(solve("floor(1.5-2.5i)"), Ok(Complex64::new(1.0, -3.0)));
+    close("exp(i*3.141592653589793)", -1.0, 0.0);
+    close("ln(-1)", 0.0, std::f64::consts::PI);
+    close("sign(3+4i)", 0.6, 0.8);
+}
+
+#[test]
+fn ordering() {
+    assert_eq!(solve("2 < 3"), Ok(Complex64::new(1.0, 0.0)));
+    assert_eq!(
+        solve("i < 1"),
+        Err(Error::EvalError(EvalError::Domain("0+1i and 1+0i cannot be ordered, one of them is not real".into(), Span::new(0, 5))))
+    );
+}
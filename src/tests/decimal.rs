@@ -1,5 +1,11 @@
-use crate::decimal::Decimal;
-use crate::solve_with;
+use crate::decimal::{set_context, Context, Decimal, ParseDecimalError, Rounding};
+use crate::eval::EvalError;
+use crate::parse::Span;
+use crate::{solve_with, Error};
+use num::traits::{Bounded, CheckedAdd, CheckedMul, CheckedNeg, Num, NumCast};
+use num::{FromPrimitive, One, Signed, ToPrimitive, Zero};
+use std::collections::HashSet;
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::str::FromStr;
 
 #[test]
@@ -10,6 +16,23 @@ fn parse() {
     assert_eq!(Decimal::from_str("11.11"), Ok(Decimal::new(1111, 2)));
     assert_eq!(Decimal::from_str("11.111"), Ok(Decimal::new(11111, 3)));
     assert_eq!(Decimal::from_str("1.2345"), Ok(Decimal::new(12345, 4)));
+    assert_eq!(Decimal::from_str("-.5"), Ok(Decimal::new(-5, 1)));
+    assert_eq!(Decimal::from_str("+2.5"), Ok(Decimal::new(25, 1)));
+    assert_eq!(Decimal::from_str("1e5"), Ok(Decimal::new(100_000, 0)));
+    assert_eq!(Decimal::from_str("1.5e-3"), Ok(Decimal::new(15, 4)));
+    assert_eq!(Decimal::from_str("1_000.5"), Ok(Decimal::new(10_005, 1)));
+    assert_eq!(Decimal::from_str(&format!("0.{}1", "0".repeat(37))), Ok(Decimal::new(1, 38)));
+}
+
+#[test]
+fn parse_errors() {
+    for input in ["", "-", ".", "1.2.3", "--1", "+-1", "-+1", "1-", " 1", "1 ", "1e", "e5", "0x10", "abc", "1.5x"] {
+        assert_eq!(Decimal::from_str(input), Err(ParseDecimalError {}), "{input:?}");
+    }
+    assert_eq!(Decimal::from_str(&format!("0.{}1", "0".repeat(38))), Err(ParseDecimalError {}));
+    assert_eq!(Decimal::from_str("1e-39"), Err(ParseDecimalError {}));
+    assert_eq!(Decimal::from_str("1e39"), Err(ParseDecimalError {}));
+    assert_eq!(Decimal::from_str_radix("1.2.3", 10), Err(ParseDecimalError {}));
 }
 
 #[test]
@@ -20,6 +43,11 @@ fn display() {
     assert_eq!(Decimal::from_str("11.11").unwrap().to_string(), "11.11".to_string());
     assert_eq!(Decimal::from_str("11.111").unwrap().to_string(), "11.111".to_string());
     assert_eq!(Decimal::from_str("1.2345").unwrap().to_string(), "1.2345".to_string());
+    assert_eq!(Decimal::from_str("1.05").unwrap().to_string(), "1.05".to_string());
+    assert_eq!(Decimal::from_str("-0.5").unwrap().to_string(), "-0.5".to_string());
+    assert_eq!(Decimal::from_str("-0.005").unwrap().to_string(), "-0.005".to_string());
+    assert_eq!(Decimal::from_str("-12.25").unwrap().to_string(), "-12.25".to_string());
+    assert_eq!(Decimal::new(1, 38).to_string(), format!("0.{}1", "0".repeat(37)));
 }
 
 #[test]
@@ -58,3 +86,251 @@ fn sub() {
 fn rem() {
     assert_eq!(solve_with("10 % 3".into()), Ok(Decimal::from_str("1").unwrap()));
 }
+
+#[test]
+fn pack() {
+    assert_eq!(solve_with("50+50".into()), Ok(Decimal::new(100, 0)));
+    assert_eq!(solve_with("1.25+1.75".into()), Ok(Decimal::new(3, 0)));
+    assert_eq!(solve_with("1.25+1.05".into()), Ok(Decimal::new(23, 1)));
+}
+
+#[test]
+fn checked_scale() {
+    assert_eq!(Decimal::new(12345, 3).checked_scale(1), Some(Decimal::new(123, 1)));
+    assert_eq!(Decimal::new(1, 0).checked_scale(Decimal::MAX_SCALE), Some(Decimal::new(10_i128.pow(38), 38)));
+    assert_eq!(Decimal::new(10, 0).checked_scale(Decimal::MAX_SCALE), None);
+    assert_eq!(Decimal::new(0, 0).checked_scale(Decimal::MAX_SCALE + 1), None);
+}
+
+#[test]
+#[should_panic(expected = "attempt to scale decimal with overflow")]
+fn scale_overflow() {
+    Decimal::new(i128::MAX, 0).scale(1);
+}
+
+#[test]
+fn pow() {
+    assert_eq!(solve_with("2^3".into()), Ok(Decimal::new(8, 0)));
+    assert_eq!(solve_with("1.5^2".into()), Ok(Decimal::new(225, 2)));
+    assert_eq!(solve_with("(0-2)^3".into()), Ok(Decimal::new(-8, 0)));
+    assert_eq!(solve_with("2^100".into()), Ok(Decimal::new(1267650600228229401496703205376, 0)));
+    assert_eq!(solve_with("0.1^38".into()), Ok(Decimal::new(1, 38)));
+    assert_eq!(solve_with("2^0".into()), Ok(Decimal::new(1, 0)));
+    assert_eq!(solve_with("0^0".into()), Ok(Decimal::new(1, 0)));
+    assert_eq!(solve_with("1^100000000000000000000".into()), Ok(Decimal::new(1, 0)));
+    assert_eq!(solve_with("(0-1)^100000000000000000001".into()), Ok(Decimal::new(-1, 0)));
+}
+
+#[test]
+fn negative_pow() {
+    assert_eq!(solve_with("2^-1".into()), Ok(Decimal::new(5, 1)));
+    assert_eq!(solve_with("0.5^-3".into()), Ok(Decimal::new(8, 0)));
+    assert_eq!(solve_with("(0-2)^-3".into()), Ok(Decimal::new(-125, 3)));
+    assert_eq!(solve_with("3^-1".into()), Ok(Decimal::new(33333333333333333333333333333333333333, 38)));
+    assert_eq!(solve_with("7^-2".into()), Ok(Decimal::new(2040816326530612244897959183673469388, 38)));
+}
+
+#[test]
+fn fractional_pow() {
+    assert_eq!(solve_with("4^0.5".into()), Ok(Decimal::new(2, 0)));
+    assert_eq!(solve_with("2^0.5".into()), Ok(Decimal::from_str("1.4142135623730950488016887242096980786").unwrap()));
+    assert_eq!(solve_with("0^0.5".into()), Ok(Decimal::new(0, 0)));
+    assert_eq!(solve_with("8^(1/1)".into()), Ok(Decimal::new(8, 0)));
+    assert_eq!(solve_with("1.5^2.5".into()), Ok(Decimal::from_str("2.7556759606310753604719445840441278160").unwrap()));
+    assert_eq!(solve_with("2^-0.5".into()), Ok(Decimal::from_str("0.70710678118654752440084436210484903928").unwrap()));
+    assert_eq!(solve_with("10^38.2".into()), Ok(Decimal::from_str("158489319246111348520210137339150701330").unwrap()));
+    assert_eq!(solve_with("10^-40.5".into()), Ok(Decimal::zero()));
+    assert!(matches!(solve_with::<Decimal>("10^38.3".into()), Err(Error::EvalError(EvalError::Overflow(_)))));
+}
+
+#[test]
+fn fractional_pow_context() {
+    set_context(Context { precision: 30, ..Context::default() });
+    assert_eq!(solve_with("2^0.5".into()), Ok(Decimal::from_str("1.41421356237309504880168872421").unwrap()));
+    set_context(Context { precision: 20, rounding: Rounding::Up, ..Context::default() });
+    assert_eq!(solve_with("2^0.5".into()), Ok(Decimal::from_str("1.4142135623730950489").unwrap()));
+    // exact powers are not pushed to the next decimal by the direction of the rounding
+    assert_eq!(solve_with("2.25^1.5 + 0.0001^0.25 + 1^0.3".into()), Ok(Decimal::from_str("4.475").unwrap()));
+    set_context(Context { precision: 1, ..Context::default() });
+    assert_eq!(solve_with("6.25^0.5".into()), Ok(Decimal::new(2, 0)));
+    set_context(Context::default());
+}
+
+#[test]
+fn large_pow() {
+    assert_eq!(solve_with("1.0000001^100000000".into()), Ok(Decimal::new(22026454781577306636469428124636295955, 33)));
+    assert_eq!(solve_with("0.9^1000000000".into()), Ok(Decimal::new(0, 0)));
+    assert_eq!(solve_with("2^1000000000".into()), Err::<Decimal, _>(Error::EvalError(EvalError::Overflow(Span::new(0, 12)))));
+    assert_eq!(solve_with("0.5^-1000".into()), Err::<Decimal, _>(Error::EvalError(EvalError::Overflow(Span::new(0, 9)))));
+}
+
+#[test]
+fn invalid_pow() {
+    assert_eq!(solve_with("(0-8)^0.5".into()), Err::<Decimal, _>(Error::EvalError(EvalError::Domain("-8 cannot be raised to the fractional power 0.5".into(), Span::new(0, 9)))));
+    assert_eq!(solve_with("0^-1".into()), Err::<Decimal, _>(Error::EvalError(EvalError::DivisionByZero(Span::new(0, 4)))));
+    assert_eq!(solve_with("0^-0.5".into()), Err::<Decimal, _>(Error::EvalError(EvalError::DivisionByZero(Span::new(0, 6)))));
+}
+
+#[test]
+fn pow_context() {
+    let pow = |precision, rounding| {
+        set_context(Context { precision, rounding, ..Context::default() });
+        let result = solve_with::<Decimal>("(0-3)^-1".into()).unwrap();
+        set_context(Context::default());
+        result
+    };
+    assert_eq!(pow(5, Rounding::HalfEven), Decimal::new(-33333, 5));
+    assert_eq!(pow(5, Rounding::Up), Decimal::new(-33334, 5));
+    assert_eq!(pow(5, Rounding::Floor), Decimal::new(-33334, 5));
+    assert_eq!(pow(5, Rounding::Ceiling), Decimal::new(-33333, 5));
+    set_context(Context { precision: 3, rounding: Rounding::Ceiling, ..Context::default() });
+    assert_eq!(solve_with("0.1^40".into()), Ok(Decimal::new(1, 38)));
+    assert_eq!(solve_with("2^0.5".into()), Ok(Decimal::new(142, 2)));
+    set_context(Context::default());
+}
+
+#[test]
+fn mul() {
+    assert_eq!(solve_with("0.5*0.5".into()), Ok(Decimal::new(25, 2)));
+    assert_eq!(solve_with("1.1*1.1".into()), Ok(Decimal::new(121, 2)));
+    assert_eq!(solve_with("(0-0.2)*3".into()), Ok(Decimal::new(-6, 1)));
+}
+
+#[test]
+fn div() {
+    assert_eq!(solve_with("1/3".into()), Ok(Decimal::new(33333333333333333333333333333333333333, 38)));
+    assert_eq!(solve_with("0.5/0.25".into()), Ok(Decimal::new(2, 0)));
+    assert_eq!(solve_with("10/4".into()), Ok(Decimal::new(25, 1)));
+    set_context(Context { precision: 30, rounding: Rounding::HalfEven, ..Context::default() });
+    assert_eq!(solve_with("2/3".into()), Ok(Decimal::new(666666666666666666666666666667, 30)));
+    set_context(Context::default());
+}
+
+fn hash(decimal: Decimal) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    decimal.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn equality_ignores_scale() {
+    assert_eq!(Decimal::new(110, 2), Decimal::new(11, 1));
+    assert_eq!(Decimal::new(0, 5), Decimal::new(0, 0));
+    assert_ne!(Decimal::new(11, 1), Decimal::new(11, 2));
+    assert_eq!(hash(Decimal::new(110, 2)), hash(Decimal::new(11, 1)));
+    assert_eq!(hash(Decimal::new(0, 7)), hash(Decimal::zero()));
+    let set = HashSet::from([Decimal::new(1, 0), Decimal::new(10, 1), Decimal::new(100, 2)]);
+    assert_eq!(set.len(), 1);
+}
+
+#[test]
+fn ordering() {
+    let mut values = vec![Decimal::new(5, 1), Decimal::new(-3, 0), Decimal::new(i128::MAX, 0), Decimal::new(1, 38), Decimal::new(-25, 2)];
+    values.sort();
+    assert_eq!(values, vec![Decimal::new(-3, 0), Decimal::new(-25, 2), Decimal::new(1, 38), Decimal::new(5, 1), Decimal::new(i128::MAX, 0)]);
+    assert!(Decimal::new(i128::MAX, 38) < Decimal::new(i128::MAX, 37));
+    assert!(Decimal::new(-1, 38) > Decimal::new(i128::MIN, 0));
+    assert_eq!(Decimal::new(3, 1).max(Decimal::new(30, 2)), Decimal::new(3, 1));
+}
+
+#[test]
+fn num_traits() {
+    fn sum<T: Num + Copy>(values: &[T]) -> T {
+        values.iter().fold(T::zero(), |a, b| a + *b)
+    }
+    assert_eq!(sum(&[Decimal::new(1, 1), Decimal::new(2, 1)]), Decimal::new(3, 1));
+    assert!(Decimal::zero().is_zero() && Decimal::one().is_one());
+    assert_eq!(Decimal::from_str_radix("-1.25", 10), Ok(Decimal::new(-125, 2)));
+    assert_eq!(Decimal::from_str_radix("ff", 16), Ok(Decimal::new(255, 0)));
+    assert_eq!(-Decimal::new(5, 1), Decimal::new(-5, 1));
+    assert_eq!(Signed::abs(&Decimal::new(-5, 1)), Decimal::new(5, 1));
+    assert_eq!(Decimal::new(-5, 1).signum(), Decimal::new(-1, 0));
+    assert_eq!(Decimal::new(1, 0).abs_sub(&Decimal::new(3, 0)), Decimal::zero());
+    assert!(Decimal::new(-1, 3).is_negative() && !Decimal::zero().is_positive());
+    assert_eq!(Decimal::max_value().checked_add(&Decimal::one()), None);
+    assert_eq!(Decimal::min_value().checked_neg(), None);
+    assert_eq!(Decimal::new(15, 1).checked_mul(&Decimal::new(2, 0)), Some(Decimal::new(3, 0)));
+}
+
+#[test]
+#[should_panic(expected = "attempt to negate with overflow")]
+fn neg_overflow() {
+    let _ = -Decimal::new(i128::MIN, 0);
+}
+
+#[test]
+fn conversions() {
+    assert_eq!(Decimal::new(-7, 0), (-7_i8).into());
+    assert_eq!(Decimal::new(u64::MAX.into(), 0), u64::MAX.into());
+    assert_eq!(Decimal::from_f64(0.1), Some(Decimal::new(1, 1)));
+    assert_eq!(Decimal::from_f32(0.1), Some(Decimal::new(1, 1)));
+    assert_eq!(Decimal::from_f64(-1234.5e-10), Some(Decimal::new(-12345, 11)));
+    assert_eq!(Decimal::from_f64(f64::NAN), None);
+    assert_eq!(Decimal::from_f64(1e40), None);
+    assert_eq!(Decimal::from_u128(u128::MAX), None);
+    assert_eq!(Decimal::try_from(2.5), Ok(Decimal::new(25, 1)));
+    assert_eq!(Decimal::new(-29, 1).to_i64(), Some(-2));
+    assert_eq!(Decimal::new(-29, 1).to_u64(), None);
+    assert_eq!(Decimal::new(1, 1).to_f64(), Some(0.1));
+    assert_eq!(-1.25_f64, Decimal::new(-125, 2).into());
+    assert_eq!(<Decimal as NumCast>::from(3_u8), Some(Decimal::new(3, 0)));
+    assert_eq!(<Decimal as NumCast>::from(0.25_f64), Some(Decimal::new(25, 2)));
+}
+
+#[test]
+fn transcendental_references() {
+    let references = [
+        ("pi()", "3.1415926535897932384626433832795028842"),
+        ("e", "2.7182818284590452353602874713526624978"),
+        ("sqrt(2)", "1.4142135623730950488016887242096980786"),
+        ("exp(1)", "2.7182818284590452353602874713526624978"),
+        ("exp(88)", "165163625499400185552832979626485876710"),
+        ("exp(-100)", "0"),
+        ("exp(-80)", "0.00000000000000000000000000000000001805"),
+        ("ln(2)", "0.69314718055994530941723212145817656808"),
+        ("ln(0.000001)", "-13.815510557964274104107948728106185246"),
+        ("sin(1)", "0.84147098480789650665250232163029899962"),
+        ("cos(1)", "0.54030230586813971740093660744297660373"),
+        ("sin(1000000)", "-0.34999350217129295211765248678077146906"),
+        ("atan(1)", "0.78539816339744830961566084581987572105"),
+        ("atan(100000000000000000000)", "1.5707963267948966192213216916397514421"),
+        ("sin(170000000000000000000000000000000000000)", "-0.77791073918051048243585976680369579642"),
+        ("ln(1.00000000000000000000000000000000000001)", "0.00000000000000000000000000000000000001"),
+        ("cos(1.5707963267948966192313216916397514421)", "0"),
+        ("atan(-0.5)", "-0.46364760900080611621425623146121440203"),
+        ("ln(170000000000000000000000000000000000000)", "88.028861784835906388915218441194602217"),
+        ("exp(-0.000001)", "0.99999900000049999983333337499999166667"),
+        ("sqrt(0.00000000000000000000000000000000000002)", "0.00000000000000000014142135623730950488"),
+        ("sin(-3)", "-0.14112000805986722210074480280811027985"),
+        ("cos(100)", "0.86231887228768393410193851395084253551"),
+    ];
+    for (input, expected) in references {
+        assert_eq!(solve_with::<Decimal>(input.into()), Ok(Decimal::from_str(expected).unwrap()), "{input}");
+    }
+}
+
+#[test]
+fn transcendental_rounding() {
+    let references = [
+        (Rounding::Down, ["3.141592653", "-0.6931471805", "-0.9092974268", "485165195.4", "1.249045772", "-0.9899924966"]),
+        (Rounding::Up, ["3.141592654", "-0.6931471806", "-0.9092974269", "485165195.5", "1.249045773", "-0.9899924967"]),
+        (Rounding::Floor, ["3.141592653", "-0.6931471806", "-0.9092974269", "485165195.4", "1.249045772", "-0.9899924967"]),
+        (Rounding::Ceiling, ["3.141592654", "-0.6931471805", "-0.9092974268", "485165195.5", "1.249045773", "-0.9899924966"]),
+        (Rounding::HalfUp, ["3.141592654", "-0.6931471806", "-0.9092974268", "485165195.4", "1.249045772", "-0.9899924966"]),
+    ];
+    for (rounding, expected) in references {
+        set_context(Context { precision: 10, rounding, ..Context::default() });
+        for (input, expected) in ["pi()", "ln(0.5)", "sin(-2)", "exp(20)", "atan(3)", "cos(3)"].into_iter().zip(expected) {
+            assert_eq!(solve_with::<Decimal>(input.into()), Ok(Decimal::from_str(expected).unwrap()), "{input} rounded {rounding:?}");
+        }
+        // exact results stay exact whichever way they are rounded
+        assert_eq!(solve_with::<Decimal>("sqrt(6.25)+cos(0)+exp(0)+ln(1)".into()), Ok(Decimal::new(45, 1)));
+    }
+    set_context(Context { rounding: Rounding::Up, ..Context::default() });
+    assert_eq!(solve_with::<Decimal>("exp(-1000)".into()), Ok(Decimal::new(1, 38)));
+    assert_eq!(solve_with::<Decimal>("sqrt(2)".into()), Ok(Decimal::from_str("1.4142135623730950488016887242096980786").unwrap()));
+    set_context(Context::default());
+    assert_eq!(solve_with::<Decimal>("exp(-1000)".into()), Ok(Decimal::zero()));
+    assert!(matches!(solve_with::<Decimal>("exp(89)".into()), Err(Error::EvalError(EvalError::Overflow(_)))));
+    assert!(matches!(solve_with::<Decimal>("ln(-1)".into()), Err(Error::EvalError(EvalError::Domain(_, _)))));
+}
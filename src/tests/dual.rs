@@ -0,0 +1,64 @@
+use super::close;
+use crate::dual::Dual;
+use crate::eval::EvalError;
+use crate::parse::Span;
+use crate::{derivative_at, Error};
+use std::collections::HashMap;
+
+fn derivative(input: &str, x: f64) -> Result<(f64, f64), Error> {
+    let variables = HashMap::from([("x".to_string(), x), ("a".to_string(), 3.0)]);
+    derivative_at(input.into(), "x", &variables).map(|result| (result.value(), result.derivative()))
+}
+
+#[test]
+fn arithmetic() {
+    assert_eq!(derivative("x", 2.0), Ok((2.0, 1.0)));
+    assert_eq!(derivative("a", 2.0), Ok((3.0, 0.0)));
+    assert_eq!(derivative("3x^2 + 2x + 1", 2.0), Ok((17.0, 14.0)));
+    assert_eq!(derivative("a*x - x/a", 3.0), Ok((8.0, 3.0 - 1.0 / 3.0)));
+    assert_eq!(derivative("1/x", 2.0), Ok((0.5, -0.25)));
+    assert_eq!(derivative("x^-2", 2.0), Ok((0.25, -0.25)));
+    assert_eq!(derivative("-x % 3", 5.0), Ok((-2.0, -1.0)));
+    assert_eq!(derivative("x > 1 ? x^2 : -x", 3.0), Ok((9.0, 6.0)));
+    assert_eq!(derivative("1/(x-2)", 2.0), Err(Error::EvalError(EvalError::DivisionByZero(Span::new(0, 7)))));
+}
+
+#[test]
+fn exponents_depending_on_the_variable() {
+    let (value, slope) = derivative("2^x", 3.0).unwrap();
+    assert!(close(value, 8.0) && close(slope, 8.0 * 2f64.ln()));
+    let (value, slope) = derivative("x^x", 2.0).unwrap();
+    assert!(close(value, 4.0) && close(slope, 4.0 * (2f64.ln() + 1.0)));
+    assert!(matches!(derivative("(-2)^x", 2.0), Err(Error::EvalError(EvalError::Domain(..)))));
+}
+
+#[test]
+fn functions() {
+    let cases: [(&str, f64, f64); 8] = [
+        ("sin(x)", 0.5, 0.5f64.cos()),
+        ("cos(x^2)", 0.5, -(0.25f64.sin()) * 2.0 * 0.5),
+        ("exp(2x)", 0.5, 2.0 * 1f64.exp()),
+        ("ln(x)", 4.0, 0.25),
+        ("sqrt(x)", 4.0, 0.25),
+        ("atan(x)", 1.0, 0.5),
+        ("tanh(x)", 0.0, 1.0),
+        ("log(10, x)", 10.0, 1.0 / 10f64.ln() / 10.0),
+    ];
+    for (input, x, expected) in cases {
+        let (_, slope) = derivative(input, x).unwrap();
+        assert!(close(slope, expected), "d/dx {input} at {x} is {slope}, not {expected}");
+    }
+    assert_eq!(derivative("max(x, 2a)", 1.0), Ok((6.0, 0.0)));
+    assert_eq!(derivative("abs(x)", -2.0), Ok((2.0, -1.0)));
+    assert_eq!(derivative("sqrt(a - 3) + x", 1.0), Ok((1.0, 1.0)));
+    assert!(matches!(derivative("floor(x)", 1.5), Err(Error::EvalError(EvalError::Unsupported("floor", ..)))));
+}
+
+#[test]
+fn missing_variable() {
+    assert_eq!(
+        derivative_at("y^2".into(), "y", &HashMap::new()),
+        Err(Error::EvalError(EvalError::VariableNotFound("y".into(), Span::new(0, 1))))
+    );
+    assert_eq!(Dual::variable(2.0).to_string(), "2 + 1ε");
+}
@@ -0,0 +1,94 @@
+use crate::decimal::{set_context, Context, Rounding};
+use crate::eval::EvalError;
+use crate::fixed::Fixed;
+use crate::parse::{ParseError, Span};
+use crate::{solve_with, Error};
+
+fn solve<const SCALE: u8>(input: &str) -> Result<String, Error> {
+    solve_with::<Fixed<SCALE>>(input.into()).map(|value| value.to_string())
+}
+
+#[test]
+fn arithmetic() {
+    assert_eq!(solve::<4>("1.25 + 0.0001"), Ok("1.2501".into()));
+    assert_eq!(solve::<4>("1/3"), Ok("0.3333".into()));
+    assert_eq!(solve::<4>("2/3"), Ok("0.6667".into()));
+    assert_eq!(solve::<4>("0.0125 * 0.5"), Ok("0.0062".into()));
+    assert_eq!(solve::<4>("-7 % 3"), Ok("-1.0000".into()));
+    assert_eq!(solve::<2>("1.07 * 1.5"), Ok("1.60".into()));
+    assert_eq!(solve::<2>("1.075"), Ok("1.08".into()));
+    assert_eq!(solve::<0>("7 / 2"), Ok("4".into()));
+    assert_eq!(solve::<0>("5 / 2"), Ok("2".into()));
+    assert_eq!(solve::<4>("1 > 0.5 && 2 == 2.0000"), Ok("1.0000".into()));
+    assert_eq!(solve::<4>("1/0"), Err(Error::EvalError(EvalError::DivisionByZero(Span::new(0, 3)))));
+    assert_eq!(solve::<38>("1 + 1"), Err(Error::EvalError(EvalError::Overflow(Span::new(0, 5)))));
+    assert_eq!(Fixed::<4>::from_raw(15).to_string(), "0.0015");
+    assert_eq!(Fixed::<4>::from_raw(-15).to_string(), "-0.0015");
+}
+
+#[test]
+fn literals() {
+    assert_eq!(solve::<4>("0.12345"), Ok("0.1234".into()));
+    assert_eq!(solve::<4>("0.12355"), Ok("0.1236".into()));
+    assert_eq!(solve::<4>("1.5e-3"), Ok("0.0015".into()));
+    assert_eq!(solve::<4>("1e-99999"), Ok("0.0000".into()));
+    assert_eq!(solve::<4>("0xff"), Ok("255.0000".into()));
+    assert_eq!(
+        solve::<4>("1e99"),
+        Err(Error::ParseError(ParseError::ValueError("1e99".into(), "calcy::fixed::Fixed<4>".into(), Span::new(0, 4))))
+    );
+}
+
+#[test]
+fn rounding() {
+    let cases = [
+        (Rounding::HalfEven, ["0.12", "-0.12", "0.14"]),
+        (Rounding::HalfUp, ["0.13", "-0.13", "0.14"]),
+        (Rounding::Down, ["0.12", "-0.12", "0.13"]),
+        (Rounding::Up, ["0.13", "-0.13", "0.14"]),
+        (Rounding::Ceiling, ["0.13", "-0.12", "0.14"]),
+        (Rounding::Floor, ["0.12", "-0.13", "0.13"]),
+    ];
+    for (rounding, expected) in cases {
+        set_context(Context { rounding, ..Context::default() });
+        assert_eq!(solve::<2>("0.25 / 2"), Ok(expected[0].into()), "{rounding:?}");
+        assert_eq!(solve::<2>("-0.25 / 2"), Ok(expected[1].into()), "{rounding:?}");
+        assert_eq!(solve::<2>("0.37^2"), Ok(expected[2].into()), "{rounding:?}");
+    }
+    set_context(Context::default());
+}
+
+#[test]
+fn pow() {
+    assert_eq!(solve::<4>("1.5^2"), Ok("2.2500".into()));
+    assert_eq!(solve::<4>("2^-3"), Ok("0.1250".into()));
+    assert_eq!(solve::<4>("(-2)^3"), Ok("-8.0000".into()));
+    assert_eq!(solve::<4>("1.0001^10000"), Ok("2.7181".into()));
+    assert_eq!(solve::<4>("0.5^200"), Ok("0.0000".into()));
+    assert_eq!(solve::<4>("0^0"), Ok("1.0000".into()));
+    assert_eq!(solve::<4>("10^40"), Err(Error::EvalError(EvalError::Overflow(Span::new(0, 5)))));
+    assert_eq!(solve::<4>("0^-1"), Err(Error::EvalError(EvalError::DivisionByZero(Span::new(0, 4)))));
+    assert_eq!(
+        solve::<4>("2.5^0.5"),
+        Err(Error::EvalError(EvalError::Domain("2.5000 cannot be raised to the fractional power 0.5000".into(), Span::new(0, 7))))
+    );
+}
+
+#[test]
+fn functions() {
+    assert_eq!(solve::<30>("sqrt(2)"), Ok("1.414213562373095048801688724210".into()));
+    assert_eq!(solve::<4>("sqrt(6.25)"), Ok("2.5000".into()));
+    assert_eq!(solve::<30>("exp(1.5)"), Ok("4.481689070338064822602055460119".into()));
+    assert_eq!(solve::<30>("ln(10)"), Ok("2.302585092994045684017991454684".into()));
+    assert_eq!(solve::<30>("cos(2)"), Ok("-0.416146836547142386997568229501".into()));
+    assert_eq!(solve::<30>("atan(-3)"), Ok("-1.249045772398254425829917077281".into()));
+    assert_eq!(solve::<30>("sin(100)"), Ok("-0.506365641109758793656557610460".into()));
+    assert_eq!(solve::<4>("pi() + e"), Ok("5.8599".into()));
+    assert_eq!(solve::<4>("exp(-1000)"), Ok("0.0000".into()));
+    assert_eq!(solve::<2>("round(2.5) + floor(-1.5) + ceil(1.01) + trunc(-2.7)"), Ok("1.00".into()));
+    assert_eq!(solve::<2>("abs(-1.5) * sign(-3) + min(1, 2) + max(1, 2)"), Ok("1.50".into()));
+    assert_eq!(
+        solve::<4>("tan(1)"),
+        Err(Error::EvalError(EvalError::Unsupported("tan", "calcy::fixed::Fixed<4>".into(), Span::new(0, 6))))
+    );
+}
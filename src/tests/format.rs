@@ -0,0 +1,106 @@
+use crate::decimal::Decimal;
+use crate::format::{ComplexForm, Format, Fraction, Notation};
+use num::complex::Complex64;
+use num::BigRational;
+
+fn format(notation: Notation, text: &str) -> String {
+    Format { notation, ..Format::default() }.numeral(text).unwrap()
+}
+
+#[test]
+fn plain() {
+    assert_eq!(Format::default().apply(&1234.5), "1234.5");
+    assert_eq!(Format::default().apply(&f64::NAN), "NaN");
+    assert_eq!(Format::default().apply(&f64::NEG_INFINITY), "-inf");
+    assert_eq!(Format::default().numeral("1e5"), None);
+}
+
+#[test]
+fn fixed() {
+    assert_eq!(format(Notation::Fixed(2), "1.005"), "1.01");
+    assert_eq!(format(Notation::Fixed(2), "-1.005"), "-1.01");
+    assert_eq!(format(Notation::Fixed(3), "2"), "2.000");
+    assert_eq!(format(Notation::Fixed(0), "2.5"), "3");
+    assert_eq!(format(Notation::Fixed(2), "-0.001"), "0.00");
+    assert_eq!(format(Notation::Fixed(1), "99.96"), "100.0");
+}
+
+#[test]
+fn significant() {
+    assert_eq!(format(Notation::Significant(3), "0.000123456"), "0.000123");
+    assert_eq!(format(Notation::Significant(3), "123456"), "123000");
+    assert_eq!(format(Notation::Significant(3), "9.996"), "10.0");
+    assert_eq!(format(Notation::Significant(2), "0"), "0.0");
+}
+
+#[test]
+fn scientific() {
+    assert_eq!(format(Notation::Scientific(None), "0.000123456"), "1.23456e-4");
+    assert_eq!(format(Notation::Scientific(None), "-123000"), "-1.23e5");
+    assert_eq!(format(Notation::Scientific(None), "0"), "0e0");
+    assert_eq!(format(Notation::Scientific(Some(3)), "9.996"), "1.00e1");
+    assert_eq!(format(Notation::Scientific(Some(1)), "0.5"), "5e-1");
+}
+
+#[test]
+fn engineering() {
+    assert_eq!(format(Notation::Engineering(None), "0.00000015"), "150e-9");
+    assert_eq!(format(Notation::Engineering(None), "12345"), "12.345e3");
+    assert_eq!(format(Notation::Engineering(Some(2)), "999999"), "1.0e6");
+    assert_eq!(format(Notation::Engineering(Some(4)), "0.5"), "500.0e-3");
+}
+
+#[test]
+fn separators() {
+    let format = Format {
+        notation: Notation::Fixed(2),
+        separator: Some('.'),
+        decimal_mark: ',',
+        ..Format::default()
+    };
+    assert_eq!(format.apply(&1234567.891), "1.234.567,89");
+    assert_eq!(format.apply(&-123.0), "-123,00");
+    let format = Format {
+        separator: Some('_'),
+        ..Format::default()
+    };
+    assert_eq!(format.apply(&Decimal::new(-1234567, 3)), "-1_234.567");
+    assert_eq!(format.apply(&100000), "100_000");
+}
+
+#[test]
+fn fractions() {
+    let ratio = |numerator: i64, denominator: i64| BigRational::new(numerator.into(), denominator.into());
+    let format = |fraction, notation| Format {
+        fraction,
+        notation,
+        ..Format::default()
+    };
+    assert_eq!(format(Fraction::Reduced, Notation::Plain).ratio(&ratio(-14, 4)), "-7/2");
+    assert_eq!(format(Fraction::Mixed, Notation::Plain).ratio(&ratio(-7, 2)), "-3 1/2");
+    assert_eq!(format(Fraction::Mixed, Notation::Plain).ratio(&ratio(1, 2)), "1/2");
+    assert_eq!(format(Fraction::Mixed, Notation::Plain).ratio(&ratio(4, 1)), "4");
+    assert_eq!(format(Fraction::Decimal, Notation::Plain).ratio(&ratio(1, 6)), "0.1(6)");
+    assert_eq!(format(Fraction::Decimal, Notation::Plain).ratio(&ratio(-22, 7)), "-3.(142857)");
+    assert_eq!(format(Fraction::Decimal, Notation::Plain).ratio(&ratio(1, 8)), "0.125");
+    assert_eq!(format(Fraction::Decimal, Notation::Plain).ratio(&ratio(5, 1)), "5");
+    assert_eq!(format(Fraction::Decimal, Notation::Plain).ratio(&ratio(1, 1009)).chars().last(), Some('…'));
+    assert_eq!(format(Fraction::Decimal, Notation::Fixed(3)).ratio(&ratio(2, 3)), "0.667");
+    assert_eq!(format(Fraction::Decimal, Notation::Scientific(Some(3))).ratio(&ratio(-1, 30000000)), "-3.33e-8");
+}
+
+#[test]
+fn complex() {
+    let rectangular = Format::default();
+    assert_eq!(rectangular.complex(&Complex64::new(1.0, 2.0)), "1+2i");
+    assert_eq!(rectangular.complex(&Complex64::new(1.5, -1.0)), "1.5-i");
+    assert_eq!(rectangular.complex(&Complex64::new(0.0, -2.0)), "-2i");
+    assert_eq!(rectangular.complex(&Complex64::new(-3.0, -0.0)), "-3");
+    let polar = Format {
+        complex: ComplexForm::Polar,
+        notation: Notation::Fixed(3),
+        ..Format::default()
+    };
+    assert_eq!(polar.complex(&Complex64::new(1.0, 2.0)), "2.236∠1.107");
+    assert_eq!(polar.complex(&Complex64::new(-1.0, 0.0)), "1.000∠3.142");
+}
@@ -0,0 +1,67 @@
+use crate::eval::EvalError;
+use crate::function::{Arity, Functions};
+use crate::number::NumberError;
+use crate::parse::{Identifiers, Span, Syntax};
+use crate::{solve, solve_vars_with, solve_with_syntax, Error};
+use std::collections::HashMap;
+
+fn functions() -> Functions<f64> {
+    let mut functions = Functions::<f64>::new();
+    functions
+        .register("clamp", Arity::Exact(3), |args| Ok(args[0].max(args[1]).min(args[2])))
+        .register("sum", Arity::AtLeast(1), |args| Ok(args.iter().sum()))
+        .register("answer", Arity::Exact(0), |_| Ok(42.0))
+        .register("lookup", Arity::Exact(1), |args| match args[0] as i64 {
+            1 => Ok(10.0),
+            2 => Ok(20.0),
+            id => Err(NumberError::Domain(format!("no entry with id {id}"))),
+        });
+    functions
+}
+
+#[test]
+fn calls() {
+    let vars = HashMap::from([("x".to_string(), 12.0)]);
+    assert_eq!(solve_vars_with("clamp(x, 0, 10)".into(), &vars, &functions()), Ok(10.0));
+    assert_eq!(solve_vars_with("clamp(-x, 0, 10)".into(), &vars, &functions()), Ok(0.0));
+    assert_eq!(solve_vars_with("sum(1, 2, 3) * 2".into(), &vars, &functions()), Ok(12.0));
+    assert_eq!(solve_vars_with("sum(lookup(1), lookup(2))".into(), &vars, &functions()), Ok(30.0));
+    assert_eq!(solve_vars_with("2answer()".into(), &vars, &functions()), Ok(84.0));
+    assert_eq!(solve_vars_with("x(2)".into(), &vars, &functions()), Ok(24.0));
+}
+
+#[test]
+fn unregistered_names_multiply() {
+    let vars = HashMap::from([("a".to_string(), 2.0), ("b".to_string(), 3.0)]);
+    assert_eq!(solve_vars_with("ab(1+1)".into(), &vars, &functions()), Ok(12.0));
+    assert_eq!(solve_vars_with("ab(1+1)".into(), &vars, &Functions::builtins()), Ok(12.0));
+    assert_eq!(solve("foo(1)".into()), Err(Error::EvalError(EvalError::VariableNotFound("f".into(), Span::new(0, 1)))));
+}
+
+#[test]
+fn call_errors() {
+    let vars = HashMap::new();
+    assert_eq!(
+        solve_vars_with("1 + clamp(1, 2)".into(), &vars, &functions()),
+        Err(Error::EvalError(EvalError::ArityMismatch("clamp".into(), Arity::Exact(3), 2, Span::new(4, 15))))
+    );
+    assert_eq!(
+        solve_vars_with("sum()".into(), &vars, &functions()),
+        Err(Error::EvalError(EvalError::ArityMismatch("sum".into(), Arity::AtLeast(1), 0, Span::new(0, 5))))
+    );
+    assert_eq!(
+        solve_vars_with("lookup(3)".into(), &vars, &functions()),
+        Err(Error::EvalError(EvalError::Domain("no entry with id 3".into(), Span::new(0, 9))))
+    );
+    let words = Syntax {
+        identifiers: Identifiers::Words,
+        ..Syntax::default()
+    };
+    assert_eq!(
+        solve_with_syntax("foo(1)".into(), &vars, &functions(), &words),
+        Err(Error::EvalError(EvalError::FunctionNotFound("foo".into(), Span::new(0, 6))))
+    );
+    assert!(solve_vars_with("clamp(1, 2,)".into(), &vars, &functions()).is_err());
+    assert!(solve_vars_with("clamp(1 2)".into(), &vars, &functions()).is_err());
+    assert!(solve_vars_with("clamp(1, 2".into(), &vars, &functions()).is_err());
+}
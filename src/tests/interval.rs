@@ -0,0 +1,127 @@
+use crate::builtins::Elementary;
+use crate::eval::EvalError;
+use crate::interval::Interval;
+use crate::number::Number;
+use crate::parse::Span;
+use crate::{solve_with, Error};
+use num::BigRational;
+
+fn solve(input: &str) -> Result<Interval, Error> {
+    solve_with::<Interval>(input.into())
+}
+
+/// Whether the interval contains the exact rational value of the literal.
+fn encloses(interval: Interval, exact: &str) -> bool {
+    let exact = crate::solve_with::<BigRational>(exact.into()).unwrap();
+    BigRational::from_float(interval.lo()).unwrap() <= exact && exact <= BigRational::from_float(interval.hi()).unwrap()
+}
+
+#[test]
+fn literals() {
+    assert_eq!(solve("2"), Ok(Interval::point(2.0)));
+    assert_eq!(solve("0.5"), Ok(Interval::point(0.5)));
+    let tenth = solve("0.1").unwrap();
+    assert_eq!(tenth.hi(), tenth.lo().next_up());
+    assert!(encloses(tenth, "1/10"));
+    assert_eq!(solve("[1.2, 1.3]").unwrap().to_string(), "[1.2, 1.3]");
+    assert_eq!(solve("[1, 2]").unwrap().to_string(), "[1, 2]");
+    assert_eq!(solve("[1, 1]").unwrap().to_string(), "[1, 1]");
+    // the closest floats are below 1.2 and above 1.3, so the bounds are already outside
+    assert!(encloses(solve("[1.2, 1.3]").unwrap(), "1.2") && encloses(solve("[1.2, 1.3]").unwrap(), "1.3"));
+}
+
+#[test]
+fn arithmetic_encloses_the_exact_result() {
+    let cases = [
+        ("0.1 + 0.2", "3/10"),
+        ("0.1 * 3", "3/10"),
+        ("1/3", "1/3"),
+        ("2/3 - 1/3", "1/3"),
+        ("0.7 * 0.7 * 0.7", "343/1000"),
+        ("1.1^10", "25937424601/10000000000"),
+    ];
+    for (input, exact) in cases {
+        let result = solve(input).unwrap();
+        assert!(encloses(result, exact), "{input} = {result} does not contain {exact}");
+        assert!(result.width() < 1e-13, "{input} = {result} is too wide");
+    }
+}
+
+#[test]
+fn tolerance_stack() {
+    let stack = solve("[9.95, 10.05] + [19.9, 20.1] - [4.98, 5.02]").unwrap();
+    assert!(stack.lo() <= 24.83 && 24.83 - stack.lo() < 1e-12);
+    assert!(stack.hi() >= 25.17 && stack.hi() - 25.17 < 1e-12);
+    assert_eq!(solve("[1, 2] * [-3, 4]"), Ok(Interval::new(-6.0, 8.0)));
+    assert_eq!(solve("[1, 2] - [1, 2]"), Ok(Interval::new(-1.0, 1.0)));
+    assert_eq!(solve("-[1, 2]"), Ok(Interval::new(-2.0, -1.0)));
+    assert_eq!(solve("2[1, 2]"), Ok(Interval::new(2.0, 4.0)));
+}
+
+#[test]
+fn division() {
+    assert_eq!(solve("[1, 2] / [4, 8]"), Ok(Interval::new(0.125, 0.5)));
+    assert_eq!(solve("[1, 2] / [0, 4]"), Ok(Interval::new(0.25, f64::INFINITY)));
+    assert_eq!(solve("[1, 2] / [-4, 0]"), Ok(Interval::new(f64::NEG_INFINITY, -0.25)));
+    assert_eq!(solve("[-2, -1] / [0, 4]"), Ok(Interval::new(f64::NEG_INFINITY, -0.25)));
+    assert_eq!(solve("[1, 2] / [-1, 1]"), Ok(Interval::new(f64::NEG_INFINITY, f64::INFINITY)));
+    assert_eq!(solve("0 / [-1, 1]"), Ok(Interval::point(0.0)));
+    assert_eq!(solve("[1, 2] / 0"), Err(Error::EvalError(EvalError::DivisionByZero(Span::new(0, 10)))));
+    assert!(matches!(solve("[1, 2] % 2"), Err(Error::EvalError(EvalError::Unsupported("remainder", ..)))));
+}
+
+#[test]
+fn overflow() {
+    let beyond = Interval::new(f64::MAX, f64::INFINITY);
+    assert_eq!(solve("1e308*10"), Ok(beyond));
+    assert_eq!(solve("1e308+1e308"), Ok(beyond));
+    assert_eq!(solve("[1, 2]*1e308*10"), Ok(beyond));
+    assert_eq!(solve("-1e308*10"), Ok(Interval::new(f64::NEG_INFINITY, -f64::MAX)));
+    assert_eq!(solve("10^400"), Ok(beyond));
+    assert_eq!(solve("1e308/1e-10"), Ok(beyond));
+    assert_eq!(solve("1e308*10 - 1e308*10"), Ok(Interval::new(f64::NEG_INFINITY, f64::INFINITY)));
+    assert_eq!(solve("(1e308*10)/(1e308*10)"), Ok(Interval::new(0.0, f64::INFINITY)));
+    assert_eq!(solve("0 * 1e308*10"), Ok(Interval::point(0.0)));
+}
+
+#[test]
+fn powers() {
+    assert_eq!(solve("[-2, 3]^2"), Ok(Interval::new(0.0, 9.0)));
+    assert_eq!(solve("[-3, -2]^2"), Ok(Interval::new(4.0, 9.0)));
+    assert_eq!(solve("[-2, 3]^3"), Ok(Interval::new(-8.0, 27.0)));
+    assert_eq!(solve("[-3, -2]^3"), Ok(Interval::new(-27.0, -8.0)));
+    assert_eq!(solve("[2, 4]^-1"), Ok(Interval::new(0.25, 0.5)));
+    assert_eq!(solve("[1, 2]^0"), Ok(Interval::point(1.0)));
+    assert!(encloses(solve("4^0.5").unwrap(), "2"));
+    assert!(matches!(solve("[-1, 4]^0.5"), Err(Error::EvalError(EvalError::Domain(..)))));
+}
+
+#[test]
+fn comparisons() {
+    assert_eq!(solve("[1, 2] < [3, 4]"), Ok(Interval::point(1.0)));
+    assert_eq!(solve("[3, 4] < [1, 2]"), Ok(Interval::point(0.0)));
+    assert_eq!(solve("2 == 2"), Ok(Interval::point(1.0)));
+    assert_eq!(
+        solve("[1, 3] < [2, 4]"),
+        Err(Error::EvalError(EvalError::Domain("[1, 3] and [2, 4] overlap, their order is uncertain".into(), Span::new(0, 15))))
+    );
+    assert_eq!(solve("[2, 1]"), Err(Error::EvalError(EvalError::Domain("the interval [2, 1] is empty".into(), Span::new(0, 6)))));
+}
+
+#[test]
+fn functions() {
+    assert_eq!(solve("abs([-3, 2])"), Ok(Interval::new(0.0, 3.0)));
+    assert_eq!(solve("floor([1.5, 2.5])"), Ok(Interval::new(1.0, 2.0)));
+    assert_eq!(solve("max([1, 4], [2, 3])"), Ok(Interval::new(2.0, 4.0)));
+    assert_eq!(solve("sin([0, 7])"), Ok(Interval::new(-1.0, 1.0)));
+    assert_eq!(solve("cos([-1, 1])").unwrap().hi(), 1.0);
+    let pi = Interval::pi().unwrap();
+    assert!(encloses(pi, "3.14159265358979323846264338327950288") && pi.width() > 0.0);
+    let sine = solve("sin([0.1, 0.2])").unwrap();
+    assert!(sine.lo() < 0.1f64.sin() && sine.hi() > 0.2f64.sin() && sine.hi() < 1.0);
+    let root = solve("sqrt(2)").unwrap();
+    assert!(root.lo() * root.lo() < 2.0 && root.hi() * root.hi() > 2.0);
+    assert!(matches!(solve("ln([-1, 1])"), Err(Error::EvalError(EvalError::Domain(..)))));
+    assert!(matches!(Interval::point(-1.0).sqrt(), Err(..)));
+    assert_eq!(Interval::from_bool(true), Interval::point(1.0));
+}
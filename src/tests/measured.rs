@@ -0,0 +1,79 @@
+use super::close;
+use crate::builtins::Elementary;
+use crate::eval::EvalError;
+use crate::function::Functions;
+use crate::measured::Measured;
+use crate::parse::Span;
+use crate::{solve_vars_with, solve_with, Error};
+use std::collections::HashMap;
+
+fn solve(input: &str) -> Result<Measured, Error> {
+    solve_with::<Measured>(input.into())
+}
+
+#[test]
+fn literals() {
+    let g = solve("9.81±0.02").unwrap();
+    assert_eq!((g.value(), g.uncertainty()), (9.81, 0.02));
+    assert_eq!(solve("9.81 +/- 0.02").unwrap().uncertainty(), 0.02);
+    assert_eq!(solve("2").unwrap().uncertainty(), 0.0);
+    assert!(matches!(solve("1±-0.1"), Err(Error::EvalError(EvalError::Domain(..)))));
+    assert_eq!(
+        solve_with::<f64>("1±0.1".into()),
+        Err(Error::EvalError(EvalError::Unsupported("uncertainty", "f64".into(), Span::new(0, 6))))
+    );
+}
+
+#[test]
+fn propagation() {
+    let sum = solve("(1±0.3) + (2±0.4)").unwrap();
+    assert!(close(sum.value(), 3.0) && close(sum.uncertainty(), 0.5));
+    let product = solve("(10±0.3) * (2±0.08)").unwrap();
+    assert!(close(product.value(), 20.0) && close(product.uncertainty(), f64::hypot(0.6, 0.8)));
+    let quotient = solve("(10±0.3) / (2±0.08)").unwrap();
+    assert!(close(quotient.value(), 5.0) && close(quotient.uncertainty(), f64::hypot(0.15, 0.2)));
+    let power = solve("(2±0.1)^3").unwrap();
+    assert!(close(power.value(), 8.0) && close(power.uncertainty(), 1.2));
+    let scaled = solve("-3 * 2±0.1").unwrap();
+    assert!(close(scaled.value(), -6.0) && close(scaled.uncertainty(), 0.3));
+    let exponent = solve("2^(3±0.1)").unwrap();
+    assert!(close(exponent.uncertainty(), 0.8 * 2f64.ln()));
+    assert!(matches!(solve("(-2)^(3±0.1)"), Err(Error::EvalError(EvalError::Domain(..)))));
+    assert_eq!(solve("1 / (0±0.1)"), Err(Error::EvalError(EvalError::DivisionByZero(Span::new(0, 12)))));
+}
+
+#[test]
+fn correlations() {
+    let variables = HashMap::from([("x".to_string(), Measured::new(3.0, 0.1))]);
+    let solve = |input: &str| solve_vars_with(input.into(), &variables, &Functions::builtins()).unwrap();
+    assert_eq!(solve("x - x").uncertainty(), 0.0);
+    assert!(close(solve("x + x").uncertainty(), 0.2));
+    assert!(close(solve("x * x").uncertainty(), solve("x^2").uncertainty()));
+    assert!(close(solve("x / x").uncertainty(), 0.0));
+    assert!(close(solve("x + (3±0.1)").uncertainty(), 0.1 * 2f64.sqrt()));
+}
+
+#[test]
+fn functions() {
+    let root = solve("sqrt(4±0.2)").unwrap();
+    assert!(close(root.value(), 2.0) && close(root.uncertainty(), 0.05));
+    let sine = solve("sin(0±0.01)").unwrap();
+    assert!(close(sine.uncertainty(), 0.01));
+    let logarithm = solve("ln(10±0.5)").unwrap();
+    assert!(close(logarithm.uncertainty(), 0.05));
+    assert!(close(Measured::new(-2.0, 0.1).abs().unwrap().uncertainty(), 0.1));
+    assert_eq!(solve("max(1±0.1, 2±0.2)").unwrap().uncertainty(), 0.2);
+    assert!(matches!(solve("floor(1±0.1)"), Err(Error::EvalError(EvalError::Unsupported("floor", ..)))));
+}
+
+#[test]
+fn display() {
+    let display = |input: &str| solve(input).unwrap().to_string();
+    assert_eq!(display("9.81±0.02"), "9.81 ± 0.02");
+    assert_eq!(display("9.8123±0.0234"), "9.81 ± 0.02");
+    assert_eq!(display("9.8123±0.0134"), "9.812 ± 0.013");
+    assert_eq!(display("12345.6±230"), "12300 ± 200");
+    assert_eq!(display("1234±12"), "1234 ± 12");
+    assert_eq!(display("2 * 1.5±0.05"), "3.00 ± 0.10");
+    assert_eq!(display("2"), "2 ± 0");
+}
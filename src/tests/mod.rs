@@ -1,5 +1,23 @@
+mod big_decimal;
+mod big_int;
+mod builtins;
+mod complex;
+mod fixed;
 mod decimal;
+mod dual;
+mod format;
+mod function;
+mod interval;
+mod measured;
+mod modular;
 mod parse;
+mod rational;
+mod rounding;
 mod solve;
 mod tokenize;
 mod macros;
+
+/// Whether two floats agree to about twelve significant digits.
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-12 * a.abs().max(b.abs()).max(1.0)
+}
@@ -0,0 +1,77 @@
+use crate::eval::EvalError;
+use crate::modular::{modulus, set_modulus, Modular};
+use crate::parse::{ParseError, Span};
+use crate::{solve_with, Error};
+use num::BigInt;
+
+fn solve(input: &str, modulus: u64) -> Result<String, Error> {
+    set_modulus(modulus.into());
+    solve_with::<Modular>(input.into()).map(|value| value.to_string())
+}
+
+#[test]
+fn arithmetic() {
+    assert_eq!(solve("5 + 9", 12), Ok("2".into()));
+    assert_eq!(solve("3 - 5", 12), Ok("10".into()));
+    assert_eq!(solve("-1", 12), Ok("11".into()));
+    assert_eq!(solve("7 * 8", 12), Ok("8".into()));
+    assert_eq!(solve("10^18 + 10^18", 1_000_000_007), Ok("98".into()));
+    assert_eq!(solve("17 % 5", 100), Ok("2".into()));
+    assert_eq!(solve("0xff + 1", 256), Ok("0".into()));
+    assert_eq!(solve("2 < 13", 12), Ok("0".into()));
+    assert_eq!(solve("25 == 1 && min(5, 13) == 1", 12), Ok("1".into()));
+    assert_eq!(solve("123456789", 1), Ok("0".into()));
+    assert!(matches!(solve("1.5", 12), Err(Error::ParseError(ParseError::ValueError(..)))));
+}
+
+#[test]
+fn division() {
+    assert_eq!(solve("1/2", 1_000_000_007), Ok("500000004".into()));
+    assert_eq!(solve("7/5", 12), Ok("11".into()));
+    assert_eq!(solve("6/3 * 3/6", 7), Ok("1".into()));
+    assert_eq!(solve("1/12", 12), Err(Error::EvalError(EvalError::DivisionByZero(Span::new(0, 4)))));
+    assert_eq!(
+        solve("1/4", 12),
+        Err(Error::EvalError(EvalError::Domain("4 has no inverse modulo 12, they share the factor 4".into(), Span::new(0, 3))))
+    );
+}
+
+#[test]
+fn pow() {
+    assert_eq!(solve("2^1000000006", 1_000_000_007), Ok("1".into()));
+    assert_eq!(solve("3^1000000007", 1_000_000_007), Ok("3".into()));
+    assert_eq!(solve("2^-1", 1_000_000_007), Ok("500000004".into()));
+    assert_eq!(solve("3^(-2) * 9", 1_000_000_007), Ok("1".into()));
+    assert_eq!(solve("0^0", 7), Ok("1".into()));
+    assert_eq!(solve("(-2)^3", 7), Ok("6".into()));
+    // textbook RSA: n = 61 * 53, e = 17, d = 2753
+    assert_eq!(solve("65^17", 3233), Ok("2790".into()));
+    assert_eq!(solve("2790^2753", 3233), Ok("65".into()));
+    assert!(matches!(solve("2^-1", 10), Err(Error::EvalError(EvalError::Domain(..)))));
+}
+
+#[test]
+fn computed_exponents() {
+    assert_eq!(solve("2^(3*5)", 7), Ok("1".into()));
+    assert_eq!(solve("2^(6+1)", 7), Ok("2".into()));
+    assert_eq!(solve("2^(0-1)", 7), Ok("4".into()));
+    assert_eq!(solve("2^(10-3)", 7), Ok("2".into()));
+    assert_eq!(solve("2^(2^3)", 7), Ok("4".into()));
+    assert_eq!(solve("3^(2^100)", 1_000_000_007), Ok("870513414".into()));
+    assert_eq!(solve("2^(10%4)", 7), Ok("4".into()));
+    assert_eq!(solve("10%4", 7), Ok("2".into()));
+    assert_eq!(solve("2^((6/3)%4)", 7), Err(Error::EvalError(EvalError::Domain("the exponent 2 is only known modulo 7".into(), Span::new(0, 11)))));
+    assert_eq!(
+        solve("2^(6/3)", 7),
+        Err(Error::EvalError(EvalError::Domain("the exponent 2 is only known modulo 7".into(), Span::new(0, 7))))
+    );
+}
+
+#[test]
+fn equality() {
+    set_modulus(7.into());
+    assert_eq!(modulus(), Some(BigInt::from(7)));
+    assert_eq!(Modular::new(9), Modular::new(2));
+    assert_eq!(Modular::new(-1).residue(), Some(6.into()));
+    assert_eq!(Modular::new(-1).to_string(), "6");
+}
@@ -1,16 +1,186 @@
-use crate::parse::Expr::{Mul, Variable};
-use crate::parse::{parse_string, tokenize, Expr, ParseError};
+use crate::function::Functions;
+use crate::parse::{parse_string, tokenize, Expr, ExprKind, ParseError, Span, Syntax};
 
 fn parse(input: String) -> Result<Expr<f64>, ParseError> {
-    parse_string(tokenize::<f64>(input)?)
+    parse_string(tokenize::<f64>(input, &Syntax::default(), &Functions::builtins())?)
+}
+
+/// Renders the parsed tree as an s-expression, ignoring spans.
+fn tree(input: &str) -> String {
+    fn render(expr: &Expr<f64>) -> String {
+        let binary = |op: &str, a: &Expr<f64>, b: &Expr<f64>| format!("({op} {} {})", render(a), render(b));
+        match &expr.kind {
+            ExprKind::Value(v) => v.to_string(),
+            ExprKind::Variable(v) => v.clone(),
+            ExprKind::Add(a, b) => binary("+", a, b),
+            ExprKind::Sub(a, b) => binary("-", a, b),
+            ExprKind::Mul(a, b) => binary("*", a, b),
+            ExprKind::Div(a, b) => binary("/", a, b),
+            ExprKind::Pow(a, b) => binary("^", a, b),
+            ExprKind::Rem(a, b) => binary("%", a, b),
+            ExprKind::Neg(a) => format!("(- {})", render(a)),
+            ExprKind::Eq(a, b) => binary("==", a, b),
+            ExprKind::Ne(a, b) => binary("!=", a, b),
+            ExprKind::Lt(a, b) => binary("<", a, b),
+            ExprKind::Le(a, b) => binary("<=", a, b),
+            ExprKind::Gt(a, b) => binary(">", a, b),
+            ExprKind::Ge(a, b) => binary(">=", a, b),
+            ExprKind::And(a, b) => binary("&&", a, b),
+            ExprKind::Or(a, b) => binary("||", a, b),
+            ExprKind::Not(a) => format!("(! {})", render(a)),
+            ExprKind::BitAnd(a, b) => binary("&", a, b),
+            ExprKind::BitOr(a, b) => binary("|", a, b),
+            ExprKind::BitXor(a, b) => binary("xor", a, b),
+            ExprKind::BitNot(a) => format!("(~ {})", render(a)),
+            ExprKind::Shl(a, b) => binary("<<", a, b),
+            ExprKind::Shr(a, b) => binary(">>", a, b),
+            ExprKind::Conditional(c, a, b) => format!("(? {} {} {})", render(c), render(a), render(b)),
+            ExprKind::Interval(a, b) => binary("[]", a, b),
+            ExprKind::Uncertain(a, b) => binary("±", a, b),
+            ExprKind::Call(name, args) => format!("({name}{})", args.iter().map(|a| format!(" {}", render(a))).collect::<String>()),
+        }
+    }
+    render(&parse(input.into()).unwrap())
 }
 
 #[test]
 fn simple_variable_parsing() {
-    assert_eq!(parse("ab".into()), Ok(Mul(Box::new(Variable("a".into())), Box::new(Variable("b".into())))));
-    assert_eq!(parse("\"a\"\"b\"".into()), Ok(Mul(Box::new(Variable("a".into())), Box::new(Variable("b".into())))));
-    assert_eq!(parse("\"a\"b".into()), Ok(Mul(Box::new(Variable("a".into())), Box::new(Variable("b".into())))));
-    assert_eq!(parse("a\"b\"".into()), Ok(Mul(Box::new(Variable("a".into())), Box::new(Variable("b".into())))));
-    assert_eq!(parse("\"ab\"\"cd\"".into()), Ok(Mul(Box::new(Variable("ab".into())), Box::new(Variable("cd".into())))));
-    assert_eq!(parse("\"abcdefg\"h".into()), Ok(Mul(Box::new(Variable("abcdefg".into())), Box::new(Variable("h".into())))));
+    assert_eq!(tree("ab"), "(* a b)");
+    assert_eq!(tree("\"a\"\"b\""), "(* a b)");
+    assert_eq!(tree("\"a\"b"), "(* a b)");
+    assert_eq!(tree("a\"b\""), "(* a b)");
+    assert_eq!(tree("\"ab\"\"cd\""), "(* ab cd)");
+    assert_eq!(tree("\"abcdefg\"h"), "(* abcdefg h)");
+}
+
+#[test]
+fn precedence() {
+    assert_eq!(tree("1+2*3"), "(+ 1 (* 2 3))");
+    assert_eq!(tree("1*2+3"), "(+ (* 1 2) 3)");
+    assert_eq!(tree("2*3^2"), "(* 2 (^ 3 2))");
+    assert_eq!(tree("(1+2)*3"), "(* (+ 1 2) 3)");
+}
+
+#[test]
+fn associativity() {
+    assert_eq!(tree("8*2/4"), "(/ (* 8 2) 4)");
+    assert_eq!(tree("8/2*4"), "(* (/ 8 2) 4)");
+    assert_eq!(tree("1-2+3"), "(+ (- 1 2) 3)");
+    assert_eq!(tree("7%4*2"), "(* (% 7 4) 2)");
+    assert_eq!(tree("2^3^2"), "(^ 2 (^ 3 2))");
+}
+
+#[test]
+fn prefix_operators() {
+    assert_eq!(tree("-a"), "(- a)");
+    assert_eq!(tree("-(2+3)"), "(- (+ 2 3))");
+    assert_eq!(tree("2*-3"), "(* 2 (- 3))");
+    assert_eq!(tree("50--2"), "(- 50 (- 2))");
+    assert_eq!(tree("-2^2"), "(- (^ 2 2))");
+    assert_eq!(tree("2^-1"), "(^ 2 (- 1))");
+    assert_eq!(tree("-2*3"), "(* (- 2) 3)");
+    assert_eq!(tree("--a"), "(- (- a))");
+    assert_eq!(tree("+a-+b"), "(- a b)");
+    assert_eq!(parse("+a".into()).unwrap().span, Span::new(0, 2));
+    assert!(parse("2*".into()).is_err());
+    assert!(parse("*2".into()).is_err());
+}
+
+#[test]
+fn function_calls() {
+    assert_eq!(tree("max(1, a+2)"), "(max 1 (+ a 2))");
+    assert_eq!(tree("pi()"), "(pi)");
+    assert_eq!(tree("2sqrt(x)^2"), "(* 2 (^ (sqrt x) 2))");
+    assert_eq!(tree("f(x)"), "(* f x)");
+    assert_eq!(tree("ab(x)"), "(* (* a b) x)");
+    assert_eq!(tree("log10(x)"), "(log10 x)");
+    assert_eq!(tree("max(min(1, 2), 3)"), "(max (min 1 2) 3)");
+    assert_eq!(parse("1 + max(1, 2)".into()).unwrap().span, Span::new(0, 13));
+    assert!(parse("max(1,)".into()).is_err());
+    assert!(parse("max(,1)".into()).is_err());
+    assert!(parse("1, 2".into()).is_err());
+}
+
+#[test]
+fn logic_precedence() {
+    assert_eq!(tree("a+1 < b*2"), "(< (+ a 1) (* b 2))");
+    assert_eq!(tree("a < b == c >= d"), "(>= (== (< a b) c) d)");
+    assert_eq!(tree("a < b && c != d || e"), "(|| (&& (< a b) (!= c d)) e)");
+    assert_eq!(tree("a || b && c"), "(|| a (&& b c))");
+    assert_eq!(tree("!a && b"), "(&& (! a) b)");
+    assert_eq!(tree("!a == b"), "(== (! a) b)");
+    assert_eq!(tree("-a <= -b"), "(<= (- a) (- b))");
+}
+
+#[test]
+fn conditionals() {
+    assert_eq!(tree("x > 100 ? x*0.9 : x"), "(? (> x 100) (* x 0.9) x)");
+    assert_eq!(tree("a ? b : c ? d : e"), "(? a b (? c d e))");
+    assert_eq!(tree("a ? b ? c : d : e"), "(? a (? b c d) e)");
+    assert_eq!(tree("a || b ? c + 1 : d"), "(? (|| a b) (+ c 1) d)");
+    assert_eq!(tree("(a ? b : c) * 2"), "(* (? a b c) 2)");
+    assert_eq!(parse("1 ? 2 : 3".into()).unwrap().span, Span::new(0, 9));
+    assert!(parse("a ? b".into()).is_err());
+    assert!(parse("a ? b , c".into()).is_err());
+    assert!(parse("a : b".into()).is_err());
+}
+
+#[test]
+fn bitwise_precedence() {
+    assert_eq!(tree("a | b xor c & d"), "(| a (xor b (& c d)))");
+    assert_eq!(tree("a & b == c"), "(== (& a b) c)");
+    assert_eq!(tree("1 + 2 << 3 - 1"), "(<< (+ 1 2) (- 3 1))");
+    assert_eq!(tree("1 << 2 >> 3"), "(>> (<< 1 2) 3)");
+    assert_eq!(tree("~a & b"), "(& (~ a) b)");
+    assert_eq!(tree("~2^3"), "(~ (^ 2 3))");
+}
+
+#[test]
+fn intervals() {
+    assert_eq!(tree("[1, 2]"), "([] 1 2)");
+    assert_eq!(tree("[a-1, a+1] * 2"), "(* ([] (- a 1) (+ a 1)) 2)");
+    assert_eq!(tree("2[1, 2]"), "(* 2 ([] 1 2))");
+    assert_eq!(tree("[1, 2]-1"), "(- ([] 1 2) 1)");
+    assert_eq!(tree("[-1, max(1, 2)]"), "([] (- 1) (max 1 2))");
+    assert!(parse("[1]".into()).is_err());
+    assert!(parse("[1, 2".into()).is_err());
+    assert!(parse("[1, 2)".into()).is_err());
+}
+
+#[test]
+fn uncertainties() {
+    assert_eq!(tree("9.81±0.02"), "(± 9.81 0.02)");
+    assert_eq!(tree("9.81 +/- 0.02"), "(± 9.81 0.02)");
+    assert_eq!(tree("2*9.81±0.02"), "(* 2 (± 9.81 0.02))");
+    assert_eq!(tree("1±0.1 + 2±0.2"), "(+ (± 1 0.1) (± 2 0.2))");
+    assert_eq!(tree("-1±0.1"), "(- (± 1 0.1))");
+    assert_eq!(tree("a^2±1"), "(± (^ a 2) 1)");
+    assert_eq!(tree("a+-1"), "(+ a (- 1))");
+}
+
+#[test]
+fn unbalanced_brackets() {
+    assert!(parse("(1+2".into()).is_err());
+    assert!(parse("1+2)".into()).is_err());
+    assert!(parse("()".into()).is_err());
+}
+
+#[test]
+fn expression_spans() {
+    let expr = parse("12 + a*3".into()).unwrap();
+    assert_eq!(expr.span, Span::new(0, 8));
+    let ExprKind::Add(left, right) = expr.kind else { panic!("expected addition") };
+    assert_eq!(left.span, Span::new(0, 2));
+    assert_eq!(right.span, Span::new(5, 8));
+    assert_eq!(parse("(1+2)".into()).unwrap().span, Span::new(0, 5));
+}
+
+#[test]
+fn error_spans() {
+    assert_eq!(parse("".into()), Err(ParseError::EmptyError(Span::new(0, 0))));
+    assert_eq!(parse("1 + 2 )".into()), Err(ParseError::UnexpectedTokenError(")".into(), Span::new(6, 7))));
+    assert_eq!(parse("1 + ".into()), Err(ParseError::UnexpectedEndError(Span::new(3, 3))));
+    assert_eq!(parse("1 + 2 # 3".into()), Err(ParseError::UnexpectedTokenError("#".into(), Span::new(6, 7))));
+    assert_eq!(parse("1 + 2..3".into()), Err(ParseError::LiteralError("2..3".into(), Span::new(4, 8))));
+    assert_eq!(parse("1 + ()".into()), Err(ParseError::EmptyError(Span::new(4, 6))));
 }
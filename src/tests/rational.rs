@@ -0,0 +1,57 @@
+use crate::decimal::{set_context, Context};
+use crate::eval::EvalError;
+use crate::parse::Span;
+use crate::{solve_with, Error};
+use num::{BigInt, BigRational};
+
+fn ratio(numerator: i64, denominator: i64) -> BigRational {
+    BigRational::new(numerator.into(), denominator.into())
+}
+
+fn solve(input: &str) -> Result<BigRational, Error> {
+    solve_with(input.into())
+}
+
+#[test]
+fn arithmetic() {
+    assert_eq!(solve("1/3 + 1/6"), Ok(ratio(1, 2)));
+    assert_eq!(solve("0.1 + 0.2"), Ok(ratio(3, 10)));
+    assert_eq!(solve("2.5e-3"), Ok(ratio(1, 400)));
+    assert_eq!(solve("(0-7) % 2"), Ok(ratio(-1, 1)));
+    assert_eq!(solve("7 % (3/2)"), Ok(ratio(1, 1)));
+    assert_eq!(solve("1/3 * 3 == 1"), Ok(ratio(1, 1)));
+    assert_eq!(solve("1/0"), Err(Error::EvalError(EvalError::DivisionByZero(Span::new(0, 3)))));
+}
+
+#[test]
+fn pow() {
+    assert_eq!(solve("(2/3)^3"), Ok(ratio(8, 27)));
+    assert_eq!(solve("(0-2)^-3"), Ok(ratio(-1, 8)));
+    assert_eq!(solve("(0-1)^100000000001"), Ok(ratio(-1, 1)));
+    assert_eq!(solve("2^200"), Ok(BigRational::from_integer(BigInt::from(2).pow(200))));
+    assert_eq!(solve("0^-1"), Err(Error::EvalError(EvalError::DivisionByZero(Span::new(0, 4)))));
+    assert_eq!(solve("2^1000000"), Err(Error::EvalError(EvalError::Overflow(Span::new(0, 9)))));
+    assert_eq!(
+        solve("4^0.5"),
+        Err(Error::EvalError(EvalError::Domain(
+            "4 cannot be raised to the non-integer power 1/2, the result would not be exact".into(),
+            Span::new(0, 5)
+        )))
+    );
+    set_context(Context {
+        max_digits: 1_000_000,
+        ..Context::default()
+    });
+    assert!(solve("2^1000000").is_ok());
+    set_context(Context::default());
+}
+
+#[test]
+fn functions() {
+    assert_eq!(solve("sqrt(9/4)"), Ok(ratio(3, 2)));
+    assert_eq!(solve("round(5/2)"), Ok(ratio(3, 1)));
+    assert_eq!(solve("floor(0-1/2)"), Ok(ratio(-1, 1)));
+    assert_eq!(solve("abs(0-1/2)"), Ok(ratio(1, 2)));
+    assert_eq!(solve("max(1/2, 2/3, 1/3)"), Ok(ratio(2, 3)));
+    assert_eq!(solve("sqrt(2)"), Err(Error::EvalError(EvalError::Domain("square root of 2 is not rational".into(), Span::new(0, 7)))));
+}
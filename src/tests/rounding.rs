@@ -0,0 +1,105 @@
+use crate::decimal::{set_context, Context, Decimal, Rounding};
+use crate::number::{Number, NumberError};
+use num::{BigInt, BigRational, One, Signed, ToPrimitive, Zero};
+use proptest::prelude::*;
+use proptest::sample::select;
+
+fn decimal() -> impl Strategy<Value = (i64, u8)> {
+    (prop_oneof![any::<i64>(), -1000..1000_i64], 0..=12_u8)
+}
+
+fn rounding() -> impl Strategy<Value = Rounding> {
+    select(vec![Rounding::HalfEven, Rounding::HalfUp, Rounding::Down, Rounding::Up, Rounding::Ceiling, Rounding::Floor])
+}
+
+fn rational((value, scale): (i64, u8)) -> BigRational {
+    BigRational::new(value.into(), BigInt::from(10).pow(scale as u32))
+}
+
+/// Rounds the exact value to `precision` significant digits and at most 38 fractional digits.
+fn reference(value: BigRational, precision: u32, rounding: Rounding) -> Result<Decimal, NumberError> {
+    if value.is_zero() {
+        return Ok(Decimal::new(0, 0));
+    }
+    let ten = BigRational::from_integer(10.into());
+    let mut length = 0_i32;
+    while value.abs() >= ten.pow(length) {
+        length += 1;
+    }
+    while value.abs() < ten.pow(length - 1) {
+        length -= 1;
+    }
+    let shift = (precision as i32 - length).min(38);
+    let scaled = value * ten.pow(shift);
+    let half = BigRational::new(1.into(), 2.into());
+    let fraction = (&scaled - scaled.trunc()).abs();
+    let away = scaled.trunc() + BigRational::from_integer(scaled.signum().to_integer());
+    let rounded = match rounding {
+        Rounding::HalfEven if fraction == half => {
+            if scaled.trunc().to_integer() % 2 == BigInt::zero() {
+                scaled.trunc()
+            } else {
+                away
+            }
+        }
+        Rounding::HalfEven | Rounding::HalfUp => scaled.round(),
+        Rounding::Down => scaled.trunc(),
+        Rounding::Up if fraction.is_zero() => scaled.trunc(),
+        Rounding::Up => away,
+        Rounding::Ceiling => scaled.ceil(),
+        Rounding::Floor => scaled.floor(),
+    };
+    let (integer, scale) = match shift {
+        0.. => (rounded.to_integer(), shift as u8),
+        _ => ((rounded * ten.pow(-shift)).to_integer(), 0),
+    };
+    let mut decimal = Decimal::new(integer.to_i128().ok_or(NumberError::Overflow)?, scale);
+    decimal.pack();
+    Ok(decimal)
+}
+
+fn with_context<T>(precision: u32, rounding: Rounding, f: impl FnOnce() -> T) -> T {
+    set_context(Context { precision, rounding, ..Context::default() });
+    let result = f();
+    set_context(Context::default());
+    result
+}
+
+#[test]
+fn half_even_ties() {
+    let divide = |a: i128, b: i128| with_context(1, Rounding::HalfEven, || Decimal::new(a, 0).try_div(Decimal::new(b, 0)).unwrap());
+    assert_eq!(divide(25, 10), Decimal::new(2, 0));
+    assert_eq!(divide(35, 10), Decimal::new(4, 0));
+    assert_eq!(divide(-25, 10), Decimal::new(-2, 0));
+    assert_eq!(with_context(2, Rounding::HalfUp, || Decimal::new(-125, 2).try_mul(Decimal::new(1, 0))), Ok(Decimal::new(-13, 1)));
+}
+
+proptest! {
+    #[test]
+    fn multiplication(a in decimal(), b in decimal(), precision in 1..=38_u32, rounding in rounding()) {
+        let result = with_context(precision, rounding, || Decimal::new(a.0.into(), a.1).try_mul(Decimal::new(b.0.into(), b.1)));
+        prop_assert_eq!(result, reference(rational(a) * rational(b), precision, rounding));
+    }
+
+    #[test]
+    fn division(a in decimal(), b in decimal(), precision in 1..=38_u32, rounding in rounding()) {
+        prop_assume!(b.0 != 0);
+        let result = with_context(precision, rounding, || Decimal::new(a.0.into(), a.1).try_div(Decimal::new(b.0.into(), b.1)));
+        prop_assert_eq!(result, reference(rational(a) / rational(b), precision, rounding));
+    }
+
+    #[test]
+    fn exact_operations(a in decimal(), b in decimal()) {
+        let (x, y) = (Decimal::new(a.0.into(), a.1), Decimal::new(b.0.into(), b.1));
+        prop_assert_eq!(x.try_add(y), reference(rational(a) + rational(b), 38, Rounding::Down));
+        prop_assert_eq!(x.try_sub(y), reference(rational(a) - rational(b), 38, Rounding::Down));
+        prop_assert_eq!(x.try_mul(y), reference(rational(a) * rational(b), 38, Rounding::Down));
+    }
+
+    #[test]
+    fn reciprocal_powers(a in decimal(), exponent in 1..=4_i32) {
+        prop_assume!(a.0 != 0);
+        let power = Decimal::new(a.0.into(), a.1).try_pow(Decimal::new((-exponent).into(), 0));
+        prop_assert_eq!(power, reference(BigRational::one() / rational(a).pow(exponent), 38, Rounding::HalfEven));
+    }
+}
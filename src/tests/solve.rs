@@ -1,4 +1,10 @@
-use crate::{solve, Error};
+use crate::decimal::Decimal;
+use crate::eval::EvalError;
+use crate::function::Functions;
+use crate::parse::{Identifiers, ParseError, Span, Syntax};
+use crate::{solve, solve_vars, solve_with, solve_with_syntax, Error};
+use std::collections::HashMap;
+use std::num::{Saturating, Wrapping};
 
 fn assert_nearly_eq(result: Result<f64, Error>, expected: f64) {
     assert!((result.unwrap() - expected).abs() < 0.000000001);
@@ -68,7 +74,6 @@ fn should_be_err() {
     assert!(solve("18+++".into()).is_err());
     assert!(solve("39--12-343ü24ü234".into()).is_err());
     assert!(solve("25&+1".into()).is_err());
-    assert!(solve("50--2".into()).is_err());
     assert!(solve("8++ß".into()).is_err());
     assert!(solve("afk#sdmf".into()).is_err());
     assert!(solve("".into()).is_err());
@@ -82,3 +87,275 @@ fn edge_cases() {
     assert_eq!(solve("0.5*0.5".into()), Ok(0.25));
     assert_nearly_eq(solve("0.1+0.2".into()), 0.3);
 }
+
+#[test]
+fn integer_precedence() {
+    assert_eq!(solve_with::<u32>("8*2/4".into()), Ok(4));
+    assert_eq!(solve_with::<u32>("8/4*2".into()), Ok(4));
+    assert_eq!(solve_with::<u32>("10-4-3".into()), Ok(3));
+    assert_eq!(solve_with::<u32>("10-4+3".into()), Ok(9));
+    assert_eq!(solve_with::<u32>("2+3*4".into()), Ok(14));
+    assert_eq!(solve_with::<u32>("2*3+4".into()), Ok(10));
+    assert_eq!(solve_with::<u32>("17%5*3".into()), Ok(6));
+    assert_eq!(solve_with::<u32>("3*17%5".into()), Ok(1));
+    assert_eq!(solve_with::<u32>("2^3^2".into()), Ok(512));
+    assert_eq!(solve_with::<u32>("2*3^2".into()), Ok(18));
+    assert_eq!(solve_with::<u32>("(2+3)*4".into()), Ok(20));
+    assert_eq!(solve_with::<u32>("100/10/5".into()), Ok(2));
+}
+
+#[test]
+fn missing_variable_span() {
+    assert_eq!(solve("1 + (2 * abc)".into()).unwrap_err().span(), Span::new(9, 10));
+    assert_eq!(solve("1 + \"rate\" * 2".into()).unwrap_err().span(), Span::new(4, 10));
+}
+
+#[test]
+fn negation() {
+    let vars = HashMap::from([("a".to_string(), 3.0)]);
+    assert_eq!(solve_vars("-a".into(), &vars), Ok(-3.0));
+    assert_eq!(solve_vars("2*-a".into(), &vars), Ok(-6.0));
+    assert_eq!(solve("-(2+3)".into()), Ok(-5.0));
+    assert_eq!(solve("2*-3".into()), Ok(-6.0));
+    assert_eq!(solve("50--2".into()), Ok(52.0));
+    assert_eq!(solve("-2^2".into()), Ok(-4.0));
+    assert_eq!(solve("(-2)^2".into()), Ok(4.0));
+    assert_eq!(solve("2^-1".into()), Ok(0.5));
+    assert_eq!(solve("+5-+2".into()), Ok(3.0));
+    assert_eq!(solve_with::<Decimal>("-(1.5+1)".into()), Ok(Decimal::new(-25, 1)));
+}
+
+#[test]
+fn unsigned_negation() {
+    assert_eq!(solve_with::<u32>("+5".into()), Ok(5));
+    assert_eq!(
+        solve_with::<u32>("2 * -3".into()),
+        Err(Error::EvalError(EvalError::Unsupported("negation", "u32".into(), Span::new(4, 6))))
+    );
+}
+
+#[test]
+fn identifier_modes() {
+    let vars = HashMap::from([("rate".to_string(), 3.0), ("time".to_string(), 4.0), ("r".to_string(), 2.0), ("a".to_string(), 5.0)]);
+    let words = Syntax {
+        identifiers: Identifiers::Words,
+        implicit_multiplication: true,
+    };
+    let explicit = Syntax {
+        identifiers: Identifiers::Words,
+        implicit_multiplication: false,
+    };
+    let functions = Functions::builtins();
+    assert_eq!(solve_with_syntax("rate*time".into(), &vars, &functions, &words), Ok(12.0));
+    assert_eq!(solve_with_syntax("2rate + sqrt(time)".into(), &vars, &functions, &words), Ok(8.0));
+    assert_eq!(solve_with_syntax("rate time".into(), &vars, &functions, &words), Ok(12.0));
+    assert_eq!(solve_with_syntax("2ra".into(), &vars, &functions, &Syntax::default()), Ok(20.0));
+    assert!(solve_with_syntax("2rate".into(), &vars, &functions, &explicit).is_err());
+    assert_eq!(solve_with_syntax("2*rate".into(), &vars, &functions, &explicit), Ok(6.0));
+    assert_eq!(
+        solve_with_syntax("rate*tim".into(), &vars, &functions, &words),
+        Err(Error::EvalError(EvalError::VariableNotFound("tim".into(), Span::new(5, 8))))
+    );
+}
+
+#[test]
+fn number_literals() {
+    assert_eq!(solve_with::<u32>("0xFF + 0b1010 + 0o10".into()), Ok(273));
+    assert_eq!(solve_with::<u32>("1_000_000 / 1e3".into()), Ok(1000));
+    assert_eq!(solve_with::<u32>("1.5e1".into()), Ok(15));
+    assert_eq!(solve_with::<u8>("0xff".into()), Ok(255));
+    assert_eq!(solve("6.02e23 * 1e-3".into()), Ok(6.02e23 * 1e-3));
+    assert_eq!(solve_with::<f32>("0x10 * 1.5".into()), Ok(24.0));
+    assert_eq!(solve_with::<Decimal>("1e-9".into()), Ok(Decimal::new(1, 9)));
+    assert_eq!(solve_with::<Decimal>("2.5e3".into()), Ok(Decimal::new(2500, 0)));
+    assert_eq!(solve_with::<Decimal>("1_234.5e-2".into()), Ok(Decimal::new(12345, 3)));
+    assert_eq!(solve_with::<Decimal>("0x7fff".into()), Ok(Decimal::new(32767, 0)));
+}
+
+#[test]
+fn unrepresentable_literals() {
+    let error = |literal: &str, type_name: &str, end: usize| Error::ParseError(ParseError::ValueError(literal.into(), type_name.into(), Span::new(2, end)));
+    assert_eq!(solve_with::<u8>("1+0x1FF".into()), Err(error("0x1FF", "u8", 7)));
+    assert_eq!(solve_with::<u8>("1+256".into()), Err(error("256", "u8", 5)));
+    assert_eq!(solve_with::<u32>("1+1.5".into()), Err(error("1.5", "u32", 5)));
+    assert_eq!(solve_with::<u32>("1+1e-3".into()), Err(error("1e-3", "u32", 6)));
+    assert_eq!(solve_with::<Decimal>("1+1e300".into()), Err(error("1e300", "calcy::decimal::Decimal", 7)));
+}
+
+#[test]
+fn comparisons() {
+    assert_eq!(solve("1 < 2".into()), Ok(1.0));
+    assert_eq!(solve("2 <= 1".into()), Ok(0.0));
+    assert_eq!(solve("2 > 1 && 1 >= 1".into()), Ok(1.0));
+    assert_eq!(solve("1 == 1.0 && 1 != 2".into()), Ok(1.0));
+    assert_eq!(solve("0/0 == 0/0".into()), Ok(0.0));
+    assert_eq!(solve("0/0 != 0/0".into()), Ok(1.0));
+    assert_eq!(solve("0/0 < 1 || 0/0 >= 1".into()), Ok(0.0));
+    assert_eq!(solve_with::<u8>("3 > 2".into()), Ok(1));
+    assert_eq!(solve_with::<Decimal>("1.10 == 1.1".into()), Ok(Decimal::new(1, 0)));
+    assert_eq!(solve_with::<Decimal>("0.25 < 0.3".into()), Ok(Decimal::new(1, 0)));
+}
+
+#[test]
+fn logic() {
+    assert_eq!(solve("!0 + !5".into()), Ok(1.0));
+    assert_eq!(solve("2 && 3".into()), Ok(1.0));
+    assert_eq!(solve("0 || 0.5".into()), Ok(1.0));
+    assert_eq!(solve("0 || 0".into()), Ok(0.0));
+    assert_eq!(solve("-1 && 1".into()), Ok(1.0));
+}
+
+#[test]
+fn conditionals() {
+    let vars = HashMap::from([("x".to_string(), 150.0)]);
+    assert_eq!(solve_vars("x > 100 ? x*0.9 : x".into(), &vars), Ok(135.0));
+    assert_eq!(solve_vars("x < 100 ? x*0.9 : x".into(), &vars), Ok(150.0));
+    assert_eq!(solve_vars("x < 100 ? 1 : x < 200 ? 2 : 3".into(), &vars), Ok(2.0));
+}
+
+#[test]
+fn short_circuit() {
+    let vars = HashMap::from([("x".to_string(), 150.0)]);
+    assert_eq!(solve_vars("x > 100 ? x : y".into(), &vars), Ok(150.0));
+    assert_eq!(solve_vars("0 && y".into(), &vars), Ok(0.0));
+    assert_eq!(solve_vars("1 || y".into(), &vars), Ok(1.0));
+    assert_eq!(solve_with::<u32>("0 ? 1/0 : 2".into()), Ok(2));
+    assert_eq!(solve_with::<u32>("1 ? 2 : 1%0".into()), Ok(2));
+    assert_eq!(solve_with::<u32>("0 && 1/0".into()), Ok(0));
+    assert_eq!(
+        solve_vars("1 && y".into(), &vars),
+        Err(Error::EvalError(EvalError::VariableNotFound("y".into(), Span::new(5, 6))))
+    );
+}
+
+#[test]
+fn bitwise() {
+    assert_eq!(solve_with::<u8>("0xF0 & 0x3C".into()), Ok(0x30));
+    assert_eq!(solve_with::<u8>("0xF0 | 0x0F".into()), Ok(0xFF));
+    assert_eq!(solve_with::<u8>("5 xor 3".into()), Ok(6));
+    assert_eq!(solve_with::<u8>("~0".into()), Ok(255));
+    assert_eq!(solve_with::<u16>("~0".into()), Ok(u16::MAX));
+    assert_eq!(solve_with::<u32>("1 << 31 >> 30".into()), Ok(2));
+    assert_eq!(solve_with::<u32>("0xFF >> 40".into()), Ok(0));
+    assert_eq!(solve_with::<usize>("6 & 3 == 2".into()), Ok(1));
+    assert_eq!(solve("3 & 1".into()), Err(Error::EvalError(EvalError::Unsupported("bitwise and", "f64".into(), Span::new(0, 5)))));
+}
+
+#[test]
+fn checked_overflow() {
+    let overflow = |start, end| Err(Error::EvalError(EvalError::Overflow(Span::new(start, end))));
+    assert_eq!(solve_with::<u8>("255+1".into()), overflow(0, 5));
+    assert_eq!(solve_with::<u8>("1 - 2".into()), overflow(0, 5));
+    assert_eq!(solve_with::<u8>("16*16".into()), overflow(0, 5));
+    assert_eq!(solve_with::<u8>("2^8".into()), overflow(0, 3));
+    assert_eq!(solve_with::<u8>("3 << 7".into()), overflow(0, 6));
+    assert_eq!(solve_with::<u8>("0 << 9".into()), Ok(0));
+    assert_eq!(solve_with::<u8>("1 << 7".into()), Ok(128));
+}
+
+#[test]
+fn wrapping_overflow() {
+    assert_eq!(solve_with::<Wrapping<u8>>("255+1".into()), Ok(Wrapping(0)));
+    assert_eq!(solve_with::<Wrapping<u8>>("1-2".into()), Ok(Wrapping(255)));
+    assert_eq!(solve_with::<Wrapping<u8>>("-1".into()), Ok(Wrapping(255)));
+    assert_eq!(solve_with::<Wrapping<u8>>("2^9".into()), Ok(Wrapping(0)));
+    assert_eq!(solve_with::<Wrapping<u8>>("3 << 7".into()), Ok(Wrapping(128)));
+    assert_eq!(solve_with::<Wrapping<u16>>("1 << 16".into()), Ok(Wrapping(0)));
+}
+
+#[test]
+fn saturating_overflow() {
+    assert_eq!(solve_with::<Saturating<u8>>("255+1".into()), Ok(Saturating(255)));
+    assert_eq!(solve_with::<Saturating<u8>>("1-2".into()), Ok(Saturating(0)));
+    assert_eq!(solve_with::<Saturating<u8>>("-5".into()), Ok(Saturating(0)));
+    assert_eq!(solve_with::<Saturating<u8>>("2^9".into()), Ok(Saturating(255)));
+    assert_eq!(solve_with::<Saturating<u8>>("3 << 7".into()), Ok(Saturating(255)));
+    assert_eq!(solve_with::<Saturating<u32>>("sqrt(17)".into()), Ok(Saturating(4)));
+}
+
+#[test]
+fn signed_integers() {
+    assert_eq!(solve_with::<i8>("-128".into()), Ok(-128));
+    assert_eq!(solve_with::<i8>("-(-127) + -128".into()), Ok(-1));
+    assert_eq!(solve_with::<i8>("-7 / 2 + -7 % 3".into()), Ok(-4));
+    assert_eq!(solve_with::<i16>("(-2)^3 - -2^2".into()), Ok(-4));
+    assert_eq!(solve_with::<i64>("-1 << 63".into()), Ok(i64::MIN));
+    assert_eq!(solve_with::<i128>("-170141183460469231731687303715884105728".into()), Ok(i128::MIN));
+    assert_eq!(solve_with::<u128>("2^127 + (2^127 - 1)".into()), Ok(u128::MAX));
+    assert_eq!(solve_with::<u64>("0xffff_ffff_ffff_ffff".into()), Ok(u64::MAX));
+    assert_eq!(solve_with::<i32>("cbrt(-28) + abs(-5) + sign(-5)".into()), Ok(1));
+    let overflow = |end| Err(Error::EvalError(EvalError::Overflow(Span::new(0, end))));
+    assert_eq!(solve_with::<i8>("-(-128)".into()), overflow(7));
+    assert_eq!(solve_with::<i8>("64 << 1".into()), overflow(7));
+    assert_eq!(solve_with::<i8>("abs(-128)".into()), overflow(9));
+    assert_eq!(solve_with::<i8>("-128 / -1".into()), overflow(9));
+    assert_eq!(solve_with::<i8>("-128 % -1".into()), Ok(0));
+    assert_eq!(solve_with::<i8>("3-128".into()), Err(Error::ParseError(ParseError::ValueError("128".into(), "i8".into(), Span::new(2, 5)))));
+}
+
+#[test]
+fn negative_exponents() {
+    fn negative<T>(input: &str, message: &str) -> Result<T, Error> {
+        Err(Error::EvalError(EvalError::Domain(message.into(), Span::new(0, input.len()))))
+    }
+    assert_eq!(solve_with::<i32>("2^-1".into()), negative("2^-1", "2 cannot be raised to the negative power -1, the result would not be an integer"));
+    assert_eq!(solve_with::<Wrapping<i8>>("3^-2".into()), negative("3^-2", "3 cannot be raised to the negative power -2, the result would not be an integer"));
+    assert_eq!(solve_with::<Saturating<i64>>("2^(0-3)".into()), negative("2^(0-3)", "2 cannot be raised to the negative power -3, the result would not be an integer"));
+    assert_eq!(solve_with::<i8>("0^-1".into()), negative("0^-1", "0 cannot be raised to the negative power -1, the result would not be an integer"));
+}
+
+#[test]
+fn powers_of_zero_and_one() {
+    assert_eq!(solve_with::<i32>("1^-2".into()), Ok(1));
+    assert_eq!(solve_with::<i32>("(-1)^-3".into()), Ok(-1));
+    assert_eq!(solve_with::<i32>("(-1)^-4".into()), Ok(1));
+    assert_eq!(solve_with::<Wrapping<i8>>("1^-2".into()), Ok(Wrapping(1)));
+    assert_eq!(solve_with::<Saturating<i64>>("(-1)^(0-3)".into()), Ok(Saturating(-1)));
+    assert_eq!(solve_with::<i64>("1^10000000000".into()), Ok(1));
+    assert_eq!(solve_with::<i64>("(-1)^10000000001".into()), Ok(-1));
+    assert_eq!(solve_with::<i64>("0^10000000000".into()), Ok(0));
+    assert_eq!(solve_with::<u64>("1^10000000000".into()), Ok(1));
+    assert_eq!(solve_with::<u64>("0^10000000000".into()), Ok(0));
+    assert_eq!(solve_with::<i8>("0^0".into()), Ok(1));
+    assert_eq!(solve_with::<i64>("2^10000000000".into()), Err(Error::EvalError(EvalError::Overflow(Span::new(0, 13)))));
+}
+
+#[test]
+fn signed_overflow_policies() {
+    assert_eq!(solve_with::<Wrapping<i8>>("127 + 1".into()), Ok(Wrapping(-128)));
+    assert_eq!(solve_with::<Wrapping<i8>>("-(-128)".into()), Ok(Wrapping(-128)));
+    assert_eq!(solve_with::<Saturating<i8>>("-(-128)".into()), Ok(Saturating(127)));
+    assert_eq!(solve_with::<Saturating<i8>>("-100 - 100".into()), Ok(Saturating(-128)));
+    assert_eq!(solve_with::<Saturating<i8>>("-65 << 1".into()), Ok(Saturating(-128)));
+    assert_eq!(solve_with::<Saturating<i8>>("(-2)^7".into()), Ok(Saturating(-128)));
+    assert_eq!(solve_with::<Saturating<i16>>("(-2)^16".into()), Ok(Saturating(i16::MAX)));
+    assert_eq!(solve_with::<Wrapping<i8>>("-128 / -1".into()), Ok(Wrapping(-128)));
+    assert_eq!(solve_with::<Wrapping<i8>>("-128 % -1".into()), Ok(Wrapping(0)));
+    assert_eq!(solve_with::<Saturating<i8>>("-128 / -1".into()), Ok(Saturating(127)));
+    assert_eq!(solve_with::<Saturating<i8>>("-128 % -1".into()), Ok(Saturating(0)));
+}
+
+#[test]
+fn division_by_zero() {
+    fn division_by_zero<T>(start: usize, end: usize) -> Result<T, Error> {
+        Err(Error::EvalError(EvalError::DivisionByZero(Span::new(start, end))))
+    }
+    assert_eq!(solve_with::<u32>("1/0".into()), division_by_zero(0, 3));
+    assert_eq!(solve_with::<usize>("2 + 5 % (1-1)".into()), division_by_zero(4, 13));
+    assert_eq!(solve_with::<Wrapping<u8>>("1/0".into()), division_by_zero(0, 3));
+    assert_eq!(solve_with::<Saturating<u16>>("1%0".into()), division_by_zero(0, 3));
+    assert_eq!(solve_with::<Saturating<i8>>("-128/0".into()), division_by_zero(0, 6));
+    assert_eq!(solve_with::<Decimal>("1.5/0".into()), division_by_zero(0, 5));
+    assert_eq!(solve_with::<Decimal>("1.5%0.0".into()), division_by_zero(0, 7));
+    assert_eq!(solve("1/0".into()), Ok(f64::INFINITY));
+}
+
+#[test]
+fn decimal_overflow() {
+    let overflow = |end| Err(Error::EvalError(EvalError::Overflow(Span::new(0, end))));
+    assert_eq!(solve_with::<Decimal>("1e20*1e20".into()), overflow(9));
+    assert_eq!(solve_with::<Decimal>("1e38 + 0.1".into()), overflow(10));
+    assert_eq!(solve_with::<Decimal>("exp(200)".into()), overflow(8));
+    assert_eq!(solve_with::<Decimal>("exp(-200)".into()), Ok(Decimal::new(0, 0)));
+    assert!(matches!(solve_with::<Decimal>("1e-39".into()), Err(Error::ParseError(ParseError::ValueError(..)))));
+}
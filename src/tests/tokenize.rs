@@ -1,11 +1,151 @@
-use crate::parse::tokenize;
-use crate::parse::Token::{AddSymbol, MulSymbol, Value, Variable};
+use crate::parse::TokenKind::{AddSymbol, ClosingBrackets, Function, MulSymbol, OpeningBrackets, SubSymbol, Value, Variable};
+use crate::function::Functions;
+use num::complex::Complex64;
+use crate::parse::{tokenize, Identifiers, Literal, ParseError, Span, Syntax, Token, TokenKind};
+
+fn kinds(input: &str) -> Result<Vec<TokenKind<f64>>, ParseError> {
+    kinds_with(input, Syntax::default())
+}
+
+fn kinds_with(input: &str, syntax: Syntax) -> Result<Vec<TokenKind<f64>>, ParseError> {
+    Ok(tokenize::<f64>(input.into(), &syntax, &Functions::builtins())?.into_iter().map(|t| t.kind).collect())
+}
 
 #[test]
 fn simple_tokenization() {
-    assert_eq!(tokenize::<f64>("2+2".into()), Ok(vec![Value(2.0), AddSymbol, Value(2.0)]));
-    assert_eq!(tokenize::<f64>("a".into()), Ok(vec![Variable("a".into())]));
-    assert_eq!(tokenize::<f64>("ab".into()), Ok(vec![Variable("a".into()), MulSymbol, Variable("b".into())]));
-    assert_eq!(tokenize::<f64>("\"abc\"\"bcd\"".into()), Ok(vec![Variable("abc".into()), MulSymbol, Variable("bcd".into())]));
-    assert_eq!(tokenize::<f64>("\"ab\"+\"bc\"".into()), Ok(vec![Variable("ab".into()), AddSymbol, Variable("bc".into())]));
+    assert_eq!(kinds("2+2"), Ok(vec![Value(2.0), AddSymbol, Value(2.0)]));
+    assert_eq!(kinds("a"), Ok(vec![Variable("a".into())]));
+    assert_eq!(kinds("ab"), Ok(vec![Variable("a".into()), MulSymbol, Variable("b".into())]));
+    assert_eq!(kinds("\"abc\"\"bcd\""), Ok(vec![Variable("abc".into()), MulSymbol, Variable("bcd".into())]));
+    assert_eq!(kinds("\"ab\"+\"bc\""), Ok(vec![Variable("ab".into()), AddSymbol, Variable("bc".into())]));
+}
+
+#[test]
+fn signs_are_symbols() {
+    assert_eq!(kinds("-1"), Ok(vec![SubSymbol, Value(1.0)]));
+    assert_eq!(kinds("(-1)"), Ok(vec![OpeningBrackets, SubSymbol, Value(1.0), ClosingBrackets]));
+    assert_eq!(kinds("2*-a"), Ok(vec![Value(2.0), MulSymbol, SubSymbol, Variable("a".into())]));
+}
+
+#[test]
+fn token_spans() {
+    assert_eq!(
+        tokenize::<f64>("12 + \"ab\"".into(), &Syntax::default(), &Functions::builtins()),
+        Ok(vec![
+            Token::new(Value(12.0), Span::new(0, 2)),
+            Token::new(AddSymbol, Span::new(3, 4)),
+            Token::new(Variable("ab".into()), Span::new(5, 9)),
+        ])
+    );
+    assert_eq!(tokenize::<f64>("ä+1".into(), &Syntax::default(), &Functions::builtins()), Err(ParseError::UnexpectedTokenError("ä".into(), Span::new(0, 2))));
+}
+
+#[test]
+fn word_identifiers() {
+    let words = Syntax {
+        identifiers: Identifiers::Words,
+        implicit_multiplication: true,
+    };
+    assert_eq!(kinds_with("rate*time", words), Ok(vec![Variable("rate".into()), MulSymbol, Variable("time".into())]));
+    assert_eq!(kinds_with("_x1 + y_2", words), Ok(vec![Variable("_x1".into()), AddSymbol, Variable("y_2".into())]));
+    assert_eq!(kinds_with("2rate", words), Ok(vec![Value(2.0), MulSymbol, Variable("rate".into())]));
+    assert_eq!(kinds_with("f(x)", words), Ok(vec![Function("f".into()), OpeningBrackets, Variable("x".into()), ClosingBrackets]));
+    assert_eq!(kinds_with("\"a\"b", words), Ok(vec![Variable("a".into()), MulSymbol, Variable("b".into())]));
+    assert_eq!(kinds("ab_"), Err(ParseError::UnexpectedTokenError("_".into(), Span::new(2, 3))));
+}
+
+#[test]
+fn explicit_multiplication() {
+    let explicit = Syntax {
+        identifiers: Identifiers::Letters,
+        implicit_multiplication: false,
+    };
+    assert_eq!(kinds_with("2ab", explicit), Ok(vec![Value(2.0), Variable("a".into()), Variable("b".into())]));
+    assert_eq!(kinds_with("2(1)", explicit), Ok(vec![Value(2.0), OpeningBrackets, Value(1.0), ClosingBrackets]));
+}
+
+#[test]
+fn number_literals() {
+    assert_eq!(kinds("6.02e23"), Ok(vec![Value(6.02e23)]));
+    assert_eq!(kinds("1e-9"), Ok(vec![Value(1e-9)]));
+    assert_eq!(kinds("2.5E+3"), Ok(vec![Value(2500.0)]));
+    assert_eq!(kinds(".5"), Ok(vec![Value(0.5)]));
+    assert_eq!(kinds("0xFF"), Ok(vec![Value(255.0)]));
+    assert_eq!(kinds("0b1010"), Ok(vec![Value(10.0)]));
+    assert_eq!(kinds("0o755"), Ok(vec![Value(493.0)]));
+    assert_eq!(kinds("1_000_000"), Ok(vec![Value(1e6)]));
+}
+
+#[test]
+fn letters_next_to_numbers() {
+    assert_eq!(kinds("2e"), Ok(vec![Value(2.0), MulSymbol, Variable("e".into())]));
+    assert_eq!(kinds("2ex"), Ok(vec![Value(2.0), MulSymbol, Variable("e".into()), MulSymbol, Variable("x".into())]));
+    assert_eq!(kinds("0xg"), Ok(vec![Value(0.0), MulSymbol, Variable("x".into()), MulSymbol, Variable("g".into())]));
+    assert_eq!(kinds("1_a"), Err(ParseError::UnexpectedTokenError("_".into(), Span::new(1, 2))));
+}
+
+#[test]
+fn malformed_literals() {
+    assert_eq!(kinds("1.2.3"), Err(ParseError::LiteralError("1.2.3".into(), Span::new(0, 5))));
+    assert_eq!(kinds("1 + ."), Err(ParseError::LiteralError(".".into(), Span::new(4, 5))));
+}
+
+#[test]
+fn whole_literals() {
+    let literal = |integer: &str, fraction: &str, exponent| Literal {
+        radix: 10,
+        integer: integer.into(),
+        fraction: fraction.into(),
+        exponent,
+    };
+    assert_eq!("1.25e3".parse(), Ok(literal("1", "25", 3)));
+    assert_eq!(".5".parse(), Ok(literal("", "5", 0)));
+    assert_eq!("1.2.3".parse::<Literal>(), Err(ParseError::LiteralError("1.2.3".into(), Span::new(0, 5))));
+    assert_eq!("1e".parse::<Literal>(), Err(ParseError::UnexpectedTokenError("e".into(), Span::new(1, 2))));
+    assert_eq!("-1".parse::<Literal>(), Err(ParseError::UnexpectedTokenError("-".into(), Span::new(0, 1))));
+}
+
+#[test]
+fn logic_symbols() {
+    use crate::parse::TokenKind::*;
+    assert_eq!(kinds("a==b!=c"), Ok(vec![Variable("a".into()), EqSymbol, Variable("b".into()), NeSymbol, Variable("c".into())]));
+    assert_eq!(kinds("1<2<=3>4>=5"), Ok(vec![Value(1.0), LtSymbol, Value(2.0), LeSymbol, Value(3.0), GtSymbol, Value(4.0), GeSymbol, Value(5.0)]));
+    assert_eq!(kinds("!a&&b||c"), Ok(vec![NotSymbol, Variable("a".into()), AndSymbol, Variable("b".into()), OrSymbol, Variable("c".into())]));
+    assert_eq!(kinds("a?b:c"), Ok(vec![Variable("a".into()), QuestionSymbol, Variable("b".into()), ColonSymbol, Variable("c".into())]));
+    assert_eq!(tokenize::<f64>("1 >= 2".into(), &Syntax::default(), &Functions::builtins()).unwrap()[1].span, Span::new(2, 4));
+    assert_eq!(kinds("a = b"), Err(ParseError::UnexpectedTokenError("=".into(), Span::new(2, 3))));
+}
+
+#[test]
+fn bitwise_symbols() {
+    use crate::parse::TokenKind::*;
+    assert_eq!(kinds("a&b|c"), Ok(vec![Variable("a".into()), BitAndSymbol, Variable("b".into()), BitOrSymbol, Variable("c".into())]));
+    assert_eq!(kinds("~1<<2>>3"), Ok(vec![BitNotSymbol, Value(1.0), ShlSymbol, Value(2.0), ShrSymbol, Value(3.0)]));
+    assert_eq!(kinds("a xor b"), Ok(vec![Variable("a".into()), XorSymbol, Variable("b".into())]));
+    assert_eq!(kinds("2 xor(1)"), Ok(vec![Value(2.0), XorSymbol, OpeningBrackets, Value(1.0), ClosingBrackets]));
+    assert_eq!(kinds_with("x or", Syntax { identifiers: Identifiers::Words, ..Syntax::default() }), Ok(vec![Variable("x".into()), MulSymbol, Variable("or".into())]));
+    assert_eq!(kinds_with("xor1 xor xor_", Syntax { identifiers: Identifiers::Words, ..Syntax::default() }), Ok(vec![Variable("xor1".into()), XorSymbol, Variable("xor_".into())]));
+}
+
+#[test]
+fn imaginary_unit() {
+    let complex = |input: &str, identifiers| tokenize::<Complex64>(input.into(), &Syntax { identifiers, ..Syntax::default() }, &Functions::builtins()).map(|tokens| tokens.into_iter().map(|t| t.kind).collect::<Vec<_>>());
+    let (one, i) = (Complex64::new(1.0, 0.0), Complex64::i());
+    assert_eq!(complex("2i", Identifiers::Letters), Ok(vec![Value(Complex64::new(2.0, 0.0)), MulSymbol, Value(i)]));
+    assert_eq!(complex("1+j", Identifiers::Letters), Ok(vec![Value(one), AddSymbol, Value(i)]));
+    assert_eq!(complex("ix", Identifiers::Letters), Ok(vec![Value(i), MulSymbol, Variable("x".into())]));
+    assert_eq!(complex("\"i\"", Identifiers::Letters), Ok(vec![Variable("i".into())]));
+    assert_eq!(complex("i*ij", Identifiers::Words), Ok(vec![Value(i), MulSymbol, Variable("ij".into())]));
+    assert_eq!(kinds("i"), Ok(vec![Variable("i".into())]));
+}
+
+#[test]
+fn negated_literals() {
+    let signed = |input: &str| tokenize::<i8>(input.into(), &Syntax::default(), &Functions::builtins()).map(|tokens| tokens.into_iter().map(|t| (t.kind, t.span)).collect::<Vec<_>>());
+    assert_eq!(signed("-128"), Ok(vec![(Value(-128), Span::new(0, 4))]));
+    assert_eq!(signed("-127"), Ok(vec![(SubSymbol, Span::new(0, 1)), (Value(127), Span::new(1, 4))]));
+    assert_eq!(signed("(- 128)"), Ok(vec![(OpeningBrackets, Span::new(0, 1)), (Value(-128), Span::new(1, 6)), (ClosingBrackets, Span::new(6, 7))]));
+    assert_eq!(signed("1*-128"), Ok(vec![(Value(1), Span::new(0, 1)), (MulSymbol, Span::new(1, 2)), (Value(-128), Span::new(2, 6))]));
+    assert_eq!(signed("1-128"), Err(ParseError::ValueError("128".into(), "i8".into(), Span::new(2, 5))));
+    assert_eq!(signed("a-128"), Err(ParseError::ValueError("128".into(), "i8".into(), Span::new(2, 5))));
 }
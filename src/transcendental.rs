@@ -0,0 +1,208 @@
+//! Elementary functions of fixed point numbers `value / 10^scale` with any number of digits.
+//!
+//! Every function works with ten more digits than asked for and truncates the result, so it is
+//! off by at most a few units in its last digit.
+
+use num::{BigInt, Integer, Signed, ToPrimitive, Zero};
+use std::f64::consts::{LOG10_E, LOG2_10, LOG2_E};
+
+/// The number of units in the last digit a function may be off by.
+const ERROR: u32 = 10;
+
+/// The guard digits a correctly rounded result is first computed with.
+const GUARD: u32 = 10;
+
+/// Guard digits beyond which a correctly rounded result stops searching and rounds its last approximation.
+const MAX_GUARD: u32 = 640;
+
+/// The digits added to the working scale, more than enough to absorb the truncation of every term of a series.
+const EXTRA: u32 = 10;
+
+/// Rounds a function computed at `digits` and some guard digits with `round`, which receives the approximation and
+/// its scale. The guard digits double until both ends of the error bound round to the same value, so the result
+/// is the correctly rounded exact value.
+///
+/// Exact results could lie on a rounding boundary forever, callers handle those arguments themselves.
+pub(crate) fn correctly_rounded<T: PartialEq>(digits: u32, approximate: impl Fn(u32) -> BigInt, round: impl Fn(&BigInt, u32) -> T) -> T {
+    let mut guard = GUARD;
+    loop {
+        let scale = digits + guard;
+        let approximation = approximate(scale);
+        let low = round(&(&approximation - ERROR), scale);
+        let high = round(&(&approximation + ERROR), scale);
+        if low == high || guard >= MAX_GUARD {
+            return high;
+        }
+        guard *= 2;
+    }
+}
+
+fn ten(power: u32) -> BigInt {
+    BigInt::from(10).pow(power)
+}
+
+/// `a * b` at the scale, truncated.
+fn mul(a: &BigInt, b: &BigInt, scale: u32) -> BigInt {
+    a * b / ten(scale)
+}
+
+/// `a / b` at the scale, truncated.
+fn div(a: &BigInt, b: &BigInt, scale: u32) -> BigInt {
+    a * ten(scale) / b
+}
+
+/// Moves a value computed at `working` digits to the smaller `scale`.
+fn truncate(value: BigInt, working: u32, scale: u32) -> BigInt {
+    value / ten(working - scale)
+}
+
+/// The binary logarithm of the magnitude of a positive value at the scale.
+fn log2(value: &BigInt, scale: u32) -> f64 {
+    let shift = value.bits().saturating_sub(64);
+    (value >> shift).to_f64().unwrap_or(f64::INFINITY).log2() + shift as f64 - scale as f64 * LOG2_10
+}
+
+/// The closest `f64`, only used to estimate how an argument is reduced.
+fn float(value: &BigInt, scale: u32) -> f64 {
+    let shift = scale.saturating_sub(f64::DIGITS);
+    (value / ten(shift)).to_f64().unwrap_or_default() / 10f64.powi((scale - shift) as i32)
+}
+
+/// `atan(1/n)`, or `atanh(1/n)` if `hyperbolic`.
+fn inverse_arctangent(n: u32, scale: u32, hyperbolic: bool) -> BigInt {
+    let square = BigInt::from(n) * n;
+    let mut power = ten(scale) / n;
+    let mut sum = power.clone();
+    for k in 1u32.. {
+        power /= &square;
+        if power.is_zero() {
+            break;
+        }
+        let term = &power / (2 * k + 1);
+        if hyperbolic || k.is_even() {
+            sum += term;
+        } else {
+            sum -= term;
+        }
+    }
+    sum
+}
+
+/// Machin's formula `π = 16·atan(1/5) - 4·atan(1/239)`.
+pub(crate) fn pi(scale: u32) -> BigInt {
+    let working = scale + EXTRA;
+    truncate(16 * inverse_arctangent(5, working, false) - 4 * inverse_arctangent(239, working, false), working, scale)
+}
+
+/// `ln(2) = 2·atanh(1/3)`.
+fn ln2(scale: u32) -> BigInt {
+    2 * inverse_arctangent(3, scale, true)
+}
+
+/// `e^x`, reduced to `e^r · 2^k` with `|r| ≤ ln(2)/2`, and `r` halved further before the Taylor series.
+///
+/// The result grows with `x`, so callers limit it to the range they can represent.
+pub(crate) fn exp(x: &BigInt, scale: u32) -> BigInt {
+    let float = float(x, scale);
+    let k = (float * LOG2_E).round() as i64;
+    // the reduced argument is scaled back up by 2^k, the digits it gains must be known as well
+    let working = scale + EXTRA + (float * LOG10_E).max(0.0).ceil() as u32;
+    let one = ten(working);
+    let r = x * ten(working - scale) - k * ln2(working);
+    const HALVINGS: u32 = 10;
+    let r = r >> HALVINGS;
+    let (mut sum, mut term) = (one.clone(), one.clone());
+    for n in 1u32.. {
+        term = mul(&term, &r, working) / n;
+        if term.is_zero() {
+            break;
+        }
+        sum += &term;
+    }
+    for _ in 0..HALVINGS {
+        sum = mul(&sum, &sum, working);
+    }
+    let sum = if k >= 0 { sum << k as u64 } else { sum >> (-k) as u64 };
+    truncate(sum, working, scale)
+}
+
+/// `ln(x)` of a positive `x = y · 2^k` with `y` close to one, `ln(y) = 2·atanh((y-1)/(y+1))`.
+pub(crate) fn ln(x: &BigInt, scale: u32) -> BigInt {
+    let working = scale + EXTRA;
+    let one = ten(working);
+    let k = log2(x, scale).round() as i64;
+    let x = x * ten(working - scale);
+    let y = if k >= 0 { x >> k as u64 } else { x << (-k) as u64 };
+    let z = div(&(&y - &one), &(&y + &one), working);
+    let square = mul(&z, &z, working);
+    let (mut sum, mut power) = (z.clone(), z);
+    for n in 1u32.. {
+        power = mul(&power, &square, working);
+        if power.is_zero() {
+            break;
+        }
+        sum += &power / (2 * n + 1);
+    }
+    truncate(2 * sum + k * ln2(working), working, scale)
+}
+
+/// The Taylor series `sin(r)` or `cos(r)` of a reduced `|r| ≤ π/4`.
+fn sine_series(r: &BigInt, scale: u32, cosine: bool) -> BigInt {
+    let square = mul(r, r, scale);
+    let mut term = if cosine { ten(scale) } else { r.clone() };
+    let mut sum = term.clone();
+    let offset = if cosine { 0 } else { 1 };
+    for n in 1u32.. {
+        term = -mul(&term, &square, scale) / ((2 * n - 1 + offset) * (2 * n + offset));
+        if term.is_zero() {
+            break;
+        }
+        sum += &term;
+    }
+    sum
+}
+
+/// `sin(x)`, or `cos(x)` if `cosine`, reduced by multiples of `π/2` to `|r| ≤ π/4`.
+pub(crate) fn sine(x: &BigInt, scale: u32, cosine: bool) -> BigInt {
+    // each multiple of π/2 removed multiplies the error of π, so large arguments need more digits of it
+    let working = scale + EXTRA + (x.abs() / ten(scale)).to_string().len() as u32;
+    let x = x * ten(working - scale);
+    let half_pi = pi(working) / 2u8;
+    let quadrant = (&x * 2u8 + &half_pi).div_floor(&(&half_pi * 2u8));
+    let r = x - &quadrant * half_pi;
+    let quadrant = quadrant.mod_floor(&BigInt::from(4)).to_u8().unwrap() + u8::from(cosine);
+    let result = match quadrant % 4 {
+        0 => sine_series(&r, working, false),
+        1 => sine_series(&r, working, true),
+        2 => -sine_series(&r, working, false),
+        _ => -sine_series(&r, working, true),
+    };
+    truncate(result, working, scale)
+}
+
+/// `atan(x)`, arguments above one use `atan(x) = ±π/2 - atan(1/x)`, the rest is halved three times
+/// with `atan(t) = 2·atan(t / (1 + √(1 + t²)))` before the series.
+pub(crate) fn atan(x: &BigInt, scale: u32) -> BigInt {
+    let working = scale + EXTRA;
+    let one = ten(working);
+    let x = x * ten(working - scale);
+    let (mut t, offset) = match x.abs() > one {
+        true => (-div(&one, &x, working), pi(working) / 2u8 * x.signum()),
+        false => (x, BigInt::zero()),
+    };
+    const HALVINGS: u32 = 3;
+    for _ in 0..HALVINGS {
+        let root = ((&one + mul(&t, &t, working)) * &one).sqrt();
+        t = div(&t, &(&one + root), working);
+    }
+    let square = mul(&t, &t, working);
+    let (mut sum, mut power) = (t.clone(), t);
+    for n in 1u32.. {
+        power = -mul(&power, &square, working);
+        if power.is_zero() {
+            break;
+        }
+        sum += &power / (2 * n + 1);
+    }
+    truncate((sum << HALVINGS) + offset, working, scale)
+}